@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+/// What Cargo.toml asks for of a dependency
+enum Requirement {
+    /// A version requirement, such as `0.12`
+    Version(String),
+    /// A git revision, possibly abbreviated
+    Rev(String),
+}
+
+/// Read the requirement on a dependency from the `name = ...` line of a manifest
+fn requirement(manifest: &str, name: &str) -> Option<Requirement> {
+    let spec = manifest.lines().find_map(|l| {
+        let rest = l.trim().strip_prefix(name)?.trim_start().strip_prefix('=')?;
+        Some(rest.trim())
+    })?;
+    if spec.starts_with('"') {
+        return Some(Requirement::Version(spec.trim_matches('"').to_string()));
+    }
+    let key = |key: &str| {
+        spec.trim_matches(|c| c == '{' || c == '}').split(',').find_map(|entry| {
+            let value = entry.trim().strip_prefix(key)?.trim_start().strip_prefix('=')?;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    };
+    key("rev").map(Requirement::Rev).or_else(|| key("version").map(Requirement::Version))
+}
+
+/// The numeric components of a version, ignoring any pre-release or build suffix
+fn version_parts(version: &str) -> Option<Vec<u64>> {
+    let version = version.split(['-', '+']).next()?;
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Whether a version satisfies a caret requirement, Cargo's default
+///
+/// Requirements using other operators are treated as matching anything.
+fn matches_requirement(version: &str, requirement: &str) -> bool {
+    let Some(wanted) = version_parts(requirement.trim().trim_start_matches('^')) else {
+        return true;
+    };
+    let Some(version) = version_parts(version) else {
+        return false;
+    };
+    // Everything up to and including the first non-zero component must match
+    let fixed = wanted.iter().position(|&part| part != 0).map_or(wanted.len(), |i| i + 1);
+    let padded = |parts: &[u64]| (0..3).map(|i| parts.get(i).copied().unwrap_or(0)).collect();
+    let (version, wanted): (Vec<u64>, Vec<u64>) = (padded(&version), padded(&wanted));
+    version[..fixed] == wanted[..fixed] && version >= wanted
+}
+
+/// Read the value of a `key = "value"` line in a Cargo.lock package entry
+fn field(package: &str, key: &str) -> Option<String> {
+    package.lines().find_map(|l| {
+        let value = l.trim().strip_prefix(key)?.trim_start().strip_prefix('=')?;
+        Some(value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Find the locked version of a package, with the git revision if it has one
+///
+/// When several versions of the package are locked, the one satisfying
+/// `requirement` is picked, falling back to the first.
+fn locked_version(lock: &str, name: &str, requirement: Option<&Requirement>) -> Option<String> {
+    let name_line = format!("name = \"{name}\"");
+    let mut packages = lock
+        .split("[[package]]")
+        .filter(|package| package.lines().any(|l| l.trim() == name_line))
+        .peekable();
+    let first = *packages.peek()?;
+    let package = packages
+        .find(|package| match requirement {
+            Some(Requirement::Version(wanted)) => {
+                field(package, "version").is_some_and(|v| matches_requirement(&v, wanted))
+            }
+            Some(Requirement::Rev(rev)) => field(package, "source").is_some_and(|source| {
+                source.rsplit_once('#').is_some_and(|(_, locked)| locked.starts_with(rev.as_str()))
+            }),
+            None => true,
+        })
+        .unwrap_or(first);
+
+    let version = field(package, "version")?;
+    let rev = field(package, "source")
+        .filter(|source| source.starts_with("git+"))
+        .and_then(|source| Some(source.rsplit_once('#')?.1.chars().take(7).collect::<String>()));
+    Some(match rev {
+        Some(rev) => format!("{version} ({rev})"),
+        None => version,
+    })
+}
+
+/// Find the Cargo.lock that governs this build
+///
+/// A workspace member's lock file lives in the workspace root, above the
+/// manifest directory. When this crate is built as a dependency, its own
+/// directory has none at all, so the directories above the build output,
+/// usually the dependent's workspace, are searched as well.
+fn find_lock_file(manifest_dir: &Path) -> Option<PathBuf> {
+    let out_dir = std::env::var_os("OUT_DIR").map(PathBuf::from);
+    let out_ancestors = out_dir.iter().flat_map(|dir| dir.ancestors());
+    manifest_dir
+        .ancestors()
+        .chain(out_ancestors)
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+}
+
+fn main() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lock_path = find_lock_file(manifest_dir);
+    if let Some(lock_path) = &lock_path {
+        println!("cargo:rerun-if-changed={}", lock_path.display());
+    }
+
+    // Expose the versions of the embedded geometry engine to `engine_info`
+    let lock = lock_path
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let manifest = std::fs::read_to_string(manifest_dir.join("Cargo.toml")).unwrap_or_default();
+    for (name, var) in [("linesweeper", "LINESWEEPER_VERSION"), ("kurbo", "KURBO_VERSION")] {
+        let requirement = requirement(&manifest, name);
+        let version = locked_version(&lock, name, requirement.as_ref())
+            .unwrap_or_else(|| "unknown".to_string());
+        println!("cargo:rustc-env={var}={version}");
+    }
+}
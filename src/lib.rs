@@ -1,13 +1,18 @@
-use kurbo::{BezPath, Point, PathEl};
+use kurbo::{
+    Affine, BezPath, Cap, CubicBez, Join, ParamCurve, ParamCurveArclen, ParamCurveDeriv,
+    ParamCurveExtrema, ParamCurveNearest, PathEl, PathSeg, Point, Shape, Stroke, StrokeOpts,
+};
+use linesweeper::topology::{BinaryWindingNumber, Contours, Topology};
 use linesweeper::{binary_op, BinaryOp, FillRule as LsFillRule, Error as LsError};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 #[derive(uniffi::Object)]
 pub struct BezierPath {
     path: Mutex<BezPath>,
+    default_fill_rule: Mutex<FillRule>,
 }
 
-#[derive(uniffi::Enum)]
+#[derive(uniffi::Enum, Clone, Copy)]
 pub enum BooleanOperation {
     Union,
     Intersection,
@@ -15,13 +20,13 @@ pub enum BooleanOperation {
     Xor,
 }
 
-#[derive(uniffi::Enum)]
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq)]
 pub enum FillRule {
     EvenOdd,
     NonZero,
 }
 
-#[derive(uniffi::Enum)]
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq)]
 pub enum PathSegment {
     MoveTo { x: f64, y: f64 },
     LineTo { x: f64, y: f64 },
@@ -30,12 +35,247 @@ pub enum PathSegment {
     ClosePath,
 }
 
+/// Summary statistics of the lengths of a path's segments
+#[derive(uniffi::Record)]
+pub struct LengthStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub total: f64,
+    pub count: u64,
+}
+
+/// A point (or, for tangents, a direction vector) in the plane
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct Point2D {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<Point> for Point2D {
+    fn from(p: Point) -> Self {
+        Point2D { x: p.x, y: p.y }
+    }
+}
+
+impl From<Point2D> for Point {
+    fn from(p: Point2D) -> Self {
+        Point::new(p.x, p.y)
+    }
+}
+
+/// Shape at the ends of an open stroked subpath
+#[derive(uniffi::Enum, Clone, Copy)]
+pub enum LineCapKind {
+    Butt,
+    Square,
+    Round,
+}
+
+/// Shape at the corners of a stroked path
+#[derive(uniffi::Enum, Clone, Copy)]
+pub enum LineJoinKind {
+    Bevel,
+    Miter,
+    Round,
+}
+
+impl From<LineCapKind> for Cap {
+    fn from(cap: LineCapKind) -> Self {
+        match cap {
+            LineCapKind::Butt => Cap::Butt,
+            LineCapKind::Square => Cap::Square,
+            LineCapKind::Round => Cap::Round,
+        }
+    }
+}
+
+impl From<LineJoinKind> for Join {
+    fn from(join: LineJoinKind) -> Self {
+        match join {
+            LineJoinKind::Bevel => Join::Bevel,
+            LineJoinKind::Miter => Join::Miter,
+            LineJoinKind::Round => Join::Round,
+        }
+    }
+}
+
+/// Callback used by `BezierPath::map_segments` to rewrite each segment
+#[uniffi::export(callback_interface)]
+pub trait SegmentMapper: Send + Sync {
+    fn map(&self, segment: PathSegment) -> PathSegment;
+}
+
+/// The points of a path that reach furthest in each direction
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct ExtremePoints {
+    /// The point with the largest y
+    pub top: Point2D,
+    /// The point with the smallest y
+    pub bottom: Point2D,
+    /// The point with the smallest x
+    pub left: Point2D,
+    /// The point with the largest x
+    pub right: Point2D,
+}
+
+/// The closest point on a path to a query point, from `nearest_point`
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct NearestResult {
+    /// The point on the path
+    pub point: Point2D,
+    /// Its distance from the query point
+    pub distance: f64,
+}
+
+/// Versions of the geometry libraries embedded in this build
+#[derive(uniffi::Record)]
+pub struct EngineInfo {
+    pub linesweeper_version: String,
+    pub kurbo_version: String,
+    /// Whether boolean operations work on curves directly, without flattening
+    pub supports_curves: bool,
+}
+
+/// How predictably a path fills, from `BezierPath::fill_ambiguity`
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq)]
+pub enum FillAmbiguity {
+    /// Fills the same way under every fill rule
+    Clear,
+    /// Edges of the path cross each other, so some regions are covered twice
+    SelfOverlapping,
+    /// Contours don't cross, but a nested contour runs the same way as the
+    /// one around it, so it is a hole under even-odd but filled under nonzero
+    MixedWinding,
+}
+
+/// A point where two paths cross, located on each of them
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct IntersectionParam {
+    pub point: Point2D,
+    /// Index of the segment of the first path, counting closing edges
+    pub seg_a: u64,
+    /// Parameter along that segment, from 0 to 1
+    pub t_a: f64,
+    /// Index of the segment of the second path, counting closing edges
+    pub seg_b: u64,
+    /// Parameter along that segment, from 0 to 1
+    pub t_b: f64,
+}
+
+/// An axis-aligned rectangle
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl From<kurbo::Rect> for Rect {
+    fn from(rect: kurbo::Rect) -> Self {
+        Rect {
+            min_x: rect.x0,
+            min_y: rect.y0,
+            max_x: rect.x1,
+            max_y: rect.y1,
+        }
+    }
+}
+
+/// How `BezierPath::flatten_with` turns curves into lines
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq)]
+pub enum FlattenMethod {
+    /// As few lines as keep within `tolerance` of the curve, more where it
+    /// bends sharply
+    AdaptiveTolerance { tolerance: f64 },
+    /// `count` lines per curve, evenly spaced in the curve's parameter
+    UniformCount { count: u32 },
+    /// Lines of equal length per curve, as close to `length` as fits
+    UniformLength { length: f64 },
+}
+
+/// Which kinds of segment `BezierPath::get_segments_as` returns
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq)]
+pub enum SegmentMode {
+    /// The segments as they are
+    Exact,
+    /// Quadratic curves raised to the equivalent cubics
+    CubicOnly,
+    /// Curves replaced by lines within `tolerance`, as by `BezierPath::flatten`
+    LinesOnly { tolerance: f64 },
+}
+
+/// A flattened path whose size was capped, from `BezierPath::flatten_capped`
+#[derive(uniffi::Record)]
+pub struct CappedFlattening {
+    /// The path with curves replaced by lines
+    pub path: Arc<BezierPath>,
+    /// Whether the tolerance was loosened to stay within the vertex limit
+    pub truncated: bool,
+}
+
+/// Geometry of one contour of a boolean operation result, from `contour_infos`
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct ContourInfo {
+    /// Area enclosed by the contour, positive when it runs counterclockwise
+    pub signed_area: f64,
+    /// Whether the contour is the outline of a hole in an enclosing contour
+    pub is_hole: bool,
+    /// The winding number the contour adds to points inside it: 1 when it
+    /// runs counterclockwise, -1 when clockwise, 0 if it encloses no area
+    pub winding: i32,
+}
+
+/// A path packed into primitive arrays, from `BezierPath::to_flat_commands`
+#[derive(uniffi::Record, Clone, Debug, PartialEq)]
+pub struct FlatPath {
+    /// One tag per element, numbered as in `BezierPath::from_flat_commands`
+    pub tags: Vec<u8>,
+    /// The x and y of each element's points, in order
+    pub coords: Vec<f64>,
+}
+
+/// Second moments of area about a shape's centroid, from `BezierPath::moment_of_inertia`
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct MomentOfInertia {
+    /// ∫ y² dA, resisting rotation about the horizontal axis
+    pub ixx: f64,
+    /// ∫ x² dA, resisting rotation about the vertical axis
+    pub iyy: f64,
+    /// The product of inertia ∫ x·y dA
+    pub ixy: f64,
+}
+
+/// Whether a path can be used as a physics collider, from `BezierPath::physics_ready`
+#[derive(uniffi::Record, Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsReadiness {
+    /// Every subpath ends with a `ClosePath`, and there is at least one
+    pub is_closed: bool,
+    /// No subpath crosses or touches itself or another subpath
+    pub is_simple: bool,
+    /// The path encloses a non-negligible area
+    pub has_area: bool,
+    /// Every coordinate is finite
+    pub is_finite: bool,
+    /// The number of on-curve points, one per `MoveTo`, `LineTo`, `QuadTo` and `CurveTo`
+    pub vertex_count: u64,
+    /// `vertex_count` is at most the limit passed to `physics_ready`
+    pub within_vertex_limit: bool,
+    /// All of the above checks pass
+    pub ready: bool,
+}
+
 #[derive(uniffi::Error, thiserror::Error, Debug)]
 pub enum LineSweeperError {
     #[error("Input contained infinite values")]
     InfiniteInput,
     #[error("Input contained NaN values")]
     NaNInput,
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Parse error: {0}")]
+    ParseError(String),
     #[error("Linesweeper internal error: {0}")]
     InternalError(String),
 }
@@ -50,6 +290,70 @@ impl From<LsError> for LineSweeperError {
     }
 }
 
+impl BooleanOperation {
+    /// Whether a point is in the result, given whether it is in each operand
+    fn combine(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOperation::Union => in_a || in_b,
+            BooleanOperation::Intersection => in_a && in_b,
+            BooleanOperation::Difference => in_a && !in_b,
+            BooleanOperation::Xor => in_a != in_b,
+        }
+    }
+}
+
+impl FillRule {
+    /// Whether a winding number is inside under this fill rule
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+impl From<PathEl> for PathSegment {
+    fn from(el: PathEl) -> Self {
+        match el {
+            PathEl::MoveTo(p) => PathSegment::MoveTo { x: p.x, y: p.y },
+            PathEl::LineTo(p) => PathSegment::LineTo { x: p.x, y: p.y },
+            PathEl::QuadTo(p1, p2) => PathSegment::QuadTo {
+                cp_x: p1.x,
+                cp_y: p1.y,
+                x: p2.x,
+                y: p2.y,
+            },
+            PathEl::CurveTo(p1, p2, p3) => PathSegment::CurveTo {
+                cp1_x: p1.x,
+                cp1_y: p1.y,
+                cp2_x: p2.x,
+                cp2_y: p2.y,
+                x: p3.x,
+                y: p3.y,
+            },
+            PathEl::ClosePath => PathSegment::ClosePath,
+        }
+    }
+}
+
+impl From<PathSegment> for PathEl {
+    fn from(segment: PathSegment) -> Self {
+        match segment {
+            PathSegment::MoveTo { x, y } => PathEl::MoveTo(Point::new(x, y)),
+            PathSegment::LineTo { x, y } => PathEl::LineTo(Point::new(x, y)),
+            PathSegment::QuadTo { cp_x, cp_y, x, y } => {
+                PathEl::QuadTo(Point::new(cp_x, cp_y), Point::new(x, y))
+            }
+            PathSegment::CurveTo { cp1_x, cp1_y, cp2_x, cp2_y, x, y } => PathEl::CurveTo(
+                Point::new(cp1_x, cp1_y),
+                Point::new(cp2_x, cp2_y),
+                Point::new(x, y),
+            ),
+            PathSegment::ClosePath => PathEl::ClosePath,
+        }
+    }
+}
+
 impl From<BooleanOperation> for BinaryOp {
     fn from(op: BooleanOperation) -> Self {
         match op {
@@ -77,7 +381,159 @@ impl BezierPath {
     pub fn new() -> Self {
         Self {
             path: Mutex::new(BezPath::new()),
+            default_fill_rule: Mutex::new(FillRule::NonZero),
+        }
+    }
+
+    /// Create a path from a turtle graphics program
+    ///
+    /// The program is a whitespace-separated list of commands, each a letter
+    /// followed by a number: `F` and `B` draw a line forward or backward by
+    /// that many steps, `M` moves forward without drawing, and `L` and `R`
+    /// turn left (counterclockwise) or right by that many degrees. The turtle
+    /// starts at the origin facing positive x, and each step is `step` long.
+    /// Returns a `ParseError` for an unknown command or a missing or
+    /// non-finite number, and an `InvalidArgument` error if `step` isn't
+    /// finite.
+    #[uniffi::constructor]
+    pub fn from_turtle(program: String, step: f64) -> Result<Self, LineSweeperError> {
+        Ok(Self::from_kurbo_path(parse_turtle(&program, step)?))
+    }
+
+    /// Parse an SVG path data string, like the `d` attribute of `<path>`
+    ///
+    /// All SVG path commands are supported, absolute and relative, including
+    /// the `H`, `V`, `S` and `T` shorthands and elliptical arcs, which are
+    /// converted to cubic curves. Returns a parse error if the string is
+    /// malformed.
+    #[uniffi::constructor]
+    pub fn from_svg(d: String) -> Result<Self, LineSweeperError> {
+        let path =
+            BezPath::from_svg(&d).map_err(|err| LineSweeperError::ParseError(err.to_string()))?;
+        Ok(Self::from_kurbo_path(path))
+    }
+
+    /// Create a path from a list of segments, as returned by `get_segments`
+    ///
+    /// Returns an error if the list is non-empty and doesn't start with a
+    /// `MoveTo`.
+    #[uniffi::constructor]
+    pub fn from_segments(segments: Vec<PathSegment>) -> Result<Self, LineSweeperError> {
+        Ok(Self::from_kurbo_path(path_from_segments(segments)?))
+    }
+
+    /// Create a path from element tags and a packed coordinate list
+    ///
+    /// `tags` has one byte per element, as in `to_bytes`: 0 for `MoveTo`, 1
+    /// for `LineTo`, 2 for `QuadTo`, 3 for `CurveTo` and 4 for `ClosePath`.
+    /// `coords` holds the x and y of each element's points in order, so two
+    /// values for a move or line, four for a quadratic curve, six for a
+    /// cubic and none for a close. Returns an error if a tag is unknown, the
+    /// number of coordinates doesn't match the tags, or the path doesn't
+    /// start with a `MoveTo`.
+    #[uniffi::constructor]
+    pub fn from_flat_commands(tags: Vec<u8>, coords: Vec<f64>) -> Result<Self, LineSweeperError> {
+        Ok(Self::from_kurbo_path(decode_flat_commands(&tags, &coords)?))
+    }
+
+    /// Create a polygon or polyline from a packed coordinate list
+    ///
+    /// `points` holds `[x0, y0, x1, y1, ...]`: the path moves to the first
+    /// point and draws lines through the rest, then closes if `closed` is
+    /// set. An empty list gives an empty path. Returns an error if the
+    /// number of coordinates is odd.
+    #[uniffi::constructor]
+    pub fn polygon(points: Vec<f64>, closed: bool) -> Result<Self, LineSweeperError> {
+        if !points.len().is_multiple_of(2) {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "expected x and y for each point, got {} coordinates",
+                points.len()
+            )));
+        }
+        let ring = Polyline {
+            points: points.chunks_exact(2).map(|xy| Point::new(xy[0], xy[1])).collect(),
+            closed,
+        };
+        Ok(Self::from_kurbo_path(polylines_to_path(&[ring])))
+    }
+
+    /// Decode a path written by `to_bytes`
+    ///
+    /// Returns an error if the data is truncated, contains an unknown
+    /// element tag, or doesn't start with a `MoveTo`.
+    #[uniffi::constructor]
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, LineSweeperError> {
+        Ok(Self::from_kurbo_path(decode_path(&data)?))
+    }
+
+    /// Create a closed regular polygon
+    ///
+    /// The vertices lie on a circle of `radius` around (`cx`, `cy`), the
+    /// first at `rotation` radians counterclockwise from the positive x axis.
+    /// Fewer than 3 sides gives an empty path.
+    #[uniffi::constructor]
+    pub fn regular_polygon(cx: f64, cy: f64, radius: f64, sides: u32, rotation: f64) -> Self {
+        let radii = vec![radius; if sides < 3 { 0 } else { sides as usize }];
+        Self::from_kurbo_path(radial_polygon(Point::new(cx, cy), &radii, rotation))
+    }
+
+    /// Create a closed star
+    ///
+    /// The star's tips lie on a circle of `outer_r` and its inner corners on
+    /// a circle of `inner_r`, both around (`cx`, `cy`), with the first tip at
+    /// `rotation` radians counterclockwise from the positive x axis. Fewer
+    /// than 3 points gives an empty path.
+    #[uniffi::constructor]
+    pub fn star(cx: f64, cy: f64, outer_r: f64, inner_r: f64, points: u32, rotation: f64) -> Self {
+        let count = if points < 3 { 0 } else { points as usize };
+        let radii: Vec<f64> = (0..count).flat_map(|_| [outer_r, inner_r]).collect();
+        Self::from_kurbo_path(radial_polygon(Point::new(cx, cy), &radii, rotation))
+    }
+
+    /// Create a closed rectangle
+    ///
+    /// (`x`, `y`) is the corner with the smallest coordinates. A negative
+    /// width or height is treated as zero.
+    #[uniffi::constructor]
+    pub fn rectangle(x: f64, y: f64, width: f64, height: f64) -> Self {
+        let rect = kurbo::Rect::new(x, y, x + width.max(0.0), y + height.max(0.0));
+        Self::from_kurbo_path(rect.to_path(0.1))
+    }
+
+    /// Create a closed ellipse with axes along x and y
+    ///
+    /// The curves stay within a millionth of the larger radius of the true
+    /// ellipse. Negative radii are treated as zero.
+    #[uniffi::constructor]
+    pub fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64) -> Self {
+        let (rx, ry) = (rx.max(0.0), ry.max(0.0));
+        let ellipse = kurbo::Ellipse::new((cx, cy), (rx, ry), 0.0);
+        Self::from_kurbo_path(ellipse.to_path((rx.max(ry) * 1e-6).max(f64::MIN_POSITIVE)))
+    }
+
+    /// Create a closed rectangle with circular corners
+    ///
+    /// The rectangle is as in `rectangle`. The corner radius is clamped
+    /// between zero and half the shorter side, where the corners meet.
+    #[uniffi::constructor]
+    pub fn rounded_rectangle(x: f64, y: f64, width: f64, height: f64, radius: f64) -> Self {
+        let rect = kurbo::Rect::new(x, y, x + width.max(0.0), y + height.max(0.0));
+        let radius = radius.min(rect.width().min(rect.height()) / 2.0).max(0.0);
+        let rounded = kurbo::RoundedRect::from_rect(rect, radius);
+        Self::from_kurbo_path(rounded.to_path((radius * 1e-6).max(f64::MIN_POSITIVE)))
+    }
+
+    /// Create a path holding the subpaths of several paths, in order
+    ///
+    /// Subpath boundaries are kept, so the result can be passed to a single
+    /// boolean operation in place of the separate paths.
+    #[uniffi::constructor]
+    pub fn concat(paths: Vec<Arc<BezierPath>>) -> Self {
+        let mut combined = BezPath::new();
+        for path in &paths {
+            combined.extend(lock(&path.path).iter());
         }
+        Self::from_kurbo_path(combined)
     }
 }
 
@@ -86,6 +542,7 @@ impl BezierPath {
     fn from_kurbo_path(kurbo_path: BezPath) -> Self {
         Self {
             path: Mutex::new(kurbo_path),
+            default_fill_rule: Mutex::new(FillRule::NonZero),
         }
     }
 }
@@ -95,17 +552,17 @@ impl BezierPath {
 
     /// Move to a point without drawing
     pub fn move_to(&self, x: f64, y: f64) {
-        self.path.lock().unwrap().move_to(Point::new(x, y));
+        lock(&self.path).move_to(Point::new(x, y));
     }
 
     /// Draw a line to a point
     pub fn line_to(&self, x: f64, y: f64) {
-        self.path.lock().unwrap().line_to(Point::new(x, y));
+        lock(&self.path).line_to(Point::new(x, y));
     }
 
     /// Draw a cubic Bezier curve
     pub fn curve_to(&self, cp1_x: f64, cp1_y: f64, cp2_x: f64, cp2_y: f64, x: f64, y: f64) {
-        self.path.lock().unwrap().curve_to(
+        lock(&self.path).curve_to(
             Point::new(cp1_x, cp1_y),
             Point::new(cp2_x, cp2_y),
             Point::new(x, y),
@@ -114,263 +571,8185 @@ impl BezierPath {
 
     /// Draw a quadratic Bezier curve
     pub fn quad_to(&self, cp_x: f64, cp_y: f64, x: f64, y: f64) {
-        self.path.lock().unwrap().quad_to(
+        lock(&self.path).quad_to(
             Point::new(cp_x, cp_y),
             Point::new(x, y),
         );
     }
 
+    /// Move to a point without drawing, rejecting non-finite coordinates
+    ///
+    /// Unlike `move_to`, this returns an error at the offending call rather
+    /// than when the path is later used, and leaves the path unchanged.
+    pub fn try_move_to(&self, x: f64, y: f64) -> Result<(), LineSweeperError> {
+        check_points_finite([Point::new(x, y)])?;
+        self.move_to(x, y);
+        Ok(())
+    }
+
+    /// Draw a line to a point, rejecting non-finite coordinates
+    ///
+    /// The path is left unchanged on error, as with `try_move_to`.
+    pub fn try_line_to(&self, x: f64, y: f64) -> Result<(), LineSweeperError> {
+        check_points_finite([Point::new(x, y)])?;
+        self.line_to(x, y);
+        Ok(())
+    }
+
+    /// Draw a cubic Bezier curve, rejecting non-finite coordinates
+    ///
+    /// The path is left unchanged on error, as with `try_move_to`.
+    pub fn try_curve_to(
+        &self,
+        cp1_x: f64,
+        cp1_y: f64,
+        cp2_x: f64,
+        cp2_y: f64,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LineSweeperError> {
+        let points = [Point::new(cp1_x, cp1_y), Point::new(cp2_x, cp2_y), Point::new(x, y)];
+        check_points_finite(points)?;
+        self.curve_to(cp1_x, cp1_y, cp2_x, cp2_y, x, y);
+        Ok(())
+    }
+
+    /// Draw a quadratic Bezier curve, rejecting non-finite coordinates
+    ///
+    /// The path is left unchanged on error, as with `try_move_to`.
+    pub fn try_quad_to(
+        &self,
+        cp_x: f64,
+        cp_y: f64,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LineSweeperError> {
+        check_points_finite([Point::new(cp_x, cp_y), Point::new(x, y)])?;
+        self.quad_to(cp_x, cp_y, x, y);
+        Ok(())
+    }
+
     /// Close the current path
     pub fn close_path(&self) {
-        self.path.lock().unwrap().close_path();
+        lock(&self.path).close_path();
     }
 
     /// Get all segments in the path
     pub fn get_segments(&self) -> Vec<PathSegment> {
-        let path = self.path.lock().unwrap();
-        let mut segments = Vec::new();
+        let path = lock(&self.path);
+        path.elements().iter().map(|el| PathSegment::from(*el)).collect()
+    }
+
+    /// Get all segments in the path, limited to the kinds `mode` allows
+    ///
+    /// This is `get_segments` for consumers that only understand some
+    /// curves. Raising a quadratic to a cubic doesn't change its shape. A
+    /// non-positive flattening tolerance gives one line per curve.
+    pub fn get_segments_as(&self, mode: SegmentMode) -> Vec<PathSegment> {
+        match mode {
+            SegmentMode::Exact => self.get_segments(),
+            SegmentMode::CubicOnly => {
+                let raised = raise_quads(&lock(&self.path));
+                raised.elements().iter().map(|el| PathSegment::from(*el)).collect()
+            }
+            SegmentMode::LinesOnly { tolerance } => self.flatten(tolerance).get_segments(),
+        }
+    }
+
+    /// Replace every quadratic curve with the equivalent cubic, in place
+    ///
+    /// Each control point of the cubic is two thirds of the way from an end
+    /// of the quadratic to its control point, so the shape is unchanged.
+    /// Other elements are kept as they are.
+    pub fn elevate_quads_to_cubics(&self) {
+        let mut path = lock(&self.path);
+        *path = raise_quads(&path);
+    }
 
-        for el in path.elements() {
+    /// Get the number of segments in the path, as `get_segments` would return
+    pub fn segment_count(&self) -> u64 {
+        lock(&self.path).elements().len() as u64
+    }
+
+    /// Get a single segment of the path by index
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn segment_at(&self, index: u64) -> Option<PathSegment> {
+        let path = lock(&self.path);
+        let el = usize::try_from(index).ok().and_then(|i| path.elements().get(i))?;
+        Some(PathSegment::from(*el))
+    }
+
+    /// Whether the path has no segments
+    pub fn is_empty(&self) -> bool {
+        lock(&self.path).elements().is_empty()
+    }
+
+    /// Remove every segment, in place
+    ///
+    /// The path is left as if newly created, with the default fill rule
+    /// reset to nonzero, but keeps its allocation for reuse.
+    pub fn clear(&self) {
+        lock(&self.path).truncate(0);
+        *lock(&self.default_fill_rule) = FillRule::NonZero;
+    }
+
+    /// Get a copy of the path in which every subpath starts with a `MoveTo`
+    ///
+    /// A subpath that begins with a drawing command gets a `MoveTo` to the
+    /// point it implicitly starts from, which kurbo takes to be the origin
+    /// at the start of the path and the start of the previous subpath after
+    /// a `ClosePath`. The geometry is unchanged, only made explicit.
+    pub fn ensure_leading_moveto(&self) -> Arc<BezierPath> {
+        let mut normalized = BezPath::new();
+        let mut start = Point::ZERO;
+        let mut needs_move = true;
+        for el in lock(&self.path).iter() {
             match el {
                 PathEl::MoveTo(p) => {
-                    segments.push(PathSegment::MoveTo { x: p.x, y: p.y });
+                    start = p;
+                    needs_move = false;
                 }
-                PathEl::LineTo(p) => {
-                    segments.push(PathSegment::LineTo { x: p.x, y: p.y });
-                }
-                PathEl::QuadTo(p1, p2) => {
-                    segments.push(PathSegment::QuadTo {
-                        cp_x: p1.x,
-                        cp_y: p1.y,
-                        x: p2.x,
-                        y: p2.y,
-                    });
-                }
-                PathEl::CurveTo(p1, p2, p3) => {
-                    segments.push(PathSegment::CurveTo {
-                        cp1_x: p1.x,
-                        cp1_y: p1.y,
-                        cp2_x: p2.x,
-                        cp2_y: p2.y,
-                        x: p3.x,
-                        y: p3.y,
-                    });
-                }
-                PathEl::ClosePath => {
-                    segments.push(PathSegment::ClosePath);
+                PathEl::ClosePath => needs_move = true,
+                _ if needs_move => {
+                    normalized.push(PathEl::MoveTo(start));
+                    needs_move = false;
                 }
+                _ => {}
             }
+            normalized.push(el);
         }
+        Arc::new(BezierPath::from_kurbo_path(normalized))
+    }
 
-        segments
+    /// Get an independent copy of the path
+    ///
+    /// The copy has the same segments and default fill rule, and changing
+    /// either path afterwards leaves the other alone.
+    pub fn duplicate(&self) -> Arc<BezierPath> {
+        let copy = BezierPath::from_kurbo_path(self.to_kurbo_path());
+        copy.set_default_fill_rule(self.default_fill_rule());
+        Arc::new(copy)
     }
-}
 
-impl BezierPath {
-    /// Get a clone of the internal kurbo path
-    fn to_kurbo_path(&self) -> BezPath {
-        self.path.lock().unwrap().clone()
+    /// Replace the contents of the path with a list of segments
+    ///
+    /// Returns an error, leaving the path unchanged, if the list is
+    /// non-empty and doesn't start with a `MoveTo`.
+    pub fn set_segments(&self, segments: Vec<PathSegment>) -> Result<(), LineSweeperError> {
+        *lock(&self.path) = path_from_segments(segments)?;
+        Ok(())
     }
-}
 
-fn convert_contours_to_paths(contours: linesweeper::topology::Contours) -> Vec<BezierPath> {
-    contours
-        .contours()
-        .map(|contour| {
-            BezierPath::from_kurbo_path(contour.path.clone())
-        })
-        .collect()
-}
+    /// Append the elements of another path to this one
+    ///
+    /// Subpath boundaries are kept: `other`'s subpaths follow this path's
+    /// as separate subpaths. Extending a path with itself doubles it.
+    pub fn extend(&self, other: &BezierPath) {
+        let other = other.to_kurbo_path();
+        lock(&self.path).extend(other);
+    }
 
+    /// Get the min, max, mean and total length of the path's segments
+    ///
+    /// Curves are measured by arc length to within `accuracy`, and closing
+    /// edges count as segments. An empty path reports all zeros.
+    pub fn segment_length_stats(&self, accuracy: f64) -> LengthStats {
+        let path = lock(&self.path);
+        let mut stats = LengthStats {
+            min: f64::INFINITY,
+            max: 0.0,
+            mean: 0.0,
+            total: 0.0,
+            count: 0,
+        };
 
-// Create a wrapper for the result since UniFFI doesn't support Vec<Object>
-#[derive(uniffi::Record)]
-pub struct BooleanOperationResult {
-    pub paths: Vec<std::sync::Arc<BezierPath>>,
-}
+        for seg in path.segments() {
+            let length = seg.arclen(accuracy);
+            stats.min = stats.min.min(length);
+            stats.max = stats.max.max(length);
+            stats.total += length;
+            stats.count += 1;
+        }
 
-#[uniffi::export]
-pub fn boolean_operation(
-    path_a: &BezierPath,
-    path_b: &BezierPath,
-    operation: BooleanOperation,
-    fill_rule: FillRule,
-) -> Result<BooleanOperationResult, LineSweeperError> {
-    // Convert our paths to kurbo paths
-    let kurbo_a = path_a.to_kurbo_path();
-    let kurbo_b = path_b.to_kurbo_path();
+        if stats.count == 0 {
+            stats.min = 0.0;
+        } else {
+            stats.mean = stats.total / stats.count as f64;
+        }
+        stats
+    }
 
-    let result = binary_op(
-        &kurbo_a,
-        &kurbo_b,
-        LsFillRule::from(fill_rule),
-        BinaryOp::from(operation),
-    )?;
+    /// Simplify the path so that it has at most `max_vertices` vertices
+    ///
+    /// Curves are flattened, then every subpath is simplified with
+    /// Ramer-Douglas-Peucker using the smallest tolerance (found by binary
+    /// search) that meets the budget. Subpaths are never dropped, so if the
+    /// budget is smaller than the number of subpaths allows, the result is as
+    /// small as possible while keeping the same number of contours.
+    pub fn simplify_to_budget(&self, max_vertices: u64) -> Arc<BezierPath> {
+        let path = self.to_kurbo_path();
+        let bbox = path.bounding_box();
+        let size = bbox.width().hypot(bbox.height());
+        let polylines = flatten_subpaths(&path, size * 1e-4);
 
-    // Convert results back to our BezierPath type
-    let paths = convert_contours_to_paths(result)
-        .into_iter()
-        .map(std::sync::Arc::new)
-        .collect();
+        let simplify = |epsilon: f64| -> Vec<Polyline> {
+            polylines.iter().map(|p| simplify_polyline(p, epsilon)).collect()
+        };
+        let vertex_count =
+            |polylines: &[Polyline]| polylines.iter().map(|p| p.points.len() as u64).sum::<u64>();
 
-    Ok(BooleanOperationResult { paths })
-}
+        if vertex_count(&polylines) <= max_vertices {
+            return Arc::new(BezierPath::from_kurbo_path(polylines_to_path(&polylines)));
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Find the smallest tolerance that fits the budget
+        let (mut low, mut high) = (0.0, size);
+        let mut best = simplify(high);
+        for _ in 0..64 {
+            let mid = 0.5 * (low + high);
+            let candidate = simplify(mid);
+            if vertex_count(&candidate) <= max_vertices {
+                high = mid;
+                best = candidate;
+            } else {
+                low = mid;
+            }
+        }
+        Arc::new(BezierPath::from_kurbo_path(polylines_to_path(&best)))
+    }
 
-    #[test]
-    fn test_basic_path_building() {
-        let path = BezierPath::new();
-        path.move_to(0.0, 0.0);
-        path.line_to(1.0, 0.0);
-        path.line_to(1.0, 1.0);
-        path.line_to(0.0, 1.0);
-        path.close_path();
+    /// Close the open subpaths whose end already lies within `tolerance` of their start
+    ///
+    /// The end point is snapped exactly onto the start before closing, so no
+    /// closing edge is added. Subpaths whose ends are further apart are left
+    /// open, as are subpaths that are already closed.
+    pub fn close_near_subpaths(&self, tolerance: f64) -> Arc<BezierPath> {
+        let path = self.to_kurbo_path();
+        let mut result = BezPath::new();
 
-        // Verify the kurbo path is not empty
-        let kurbo_path = path.to_kurbo_path();
-        assert!(!kurbo_path.is_empty());
+        for subpath in split_subpaths(&path) {
+            let mut elements = subpath.elements().to_vec();
+            let start = match elements.first() {
+                Some(PathEl::MoveTo(p)) => *p,
+                _ => {
+                    result.extend(elements);
+                    continue;
+                }
+            };
+            let last = elements.len() - 1;
+            let is_open = last > 0 && elements[last] != PathEl::ClosePath;
+            let is_near = elements[last]
+                .end_point()
+                .is_some_and(|end| end.distance(start) <= tolerance);
+
+            if is_open && is_near {
+                elements[last] = with_end_point(elements[last], start);
+                elements.push(PathEl::ClosePath);
+            }
+            result.extend(elements);
+        }
+
+        Arc::new(BezierPath::from_kurbo_path(result))
     }
 
-    #[test]
-    fn test_curve_path() {
-        let path = BezierPath::new();
-        path.move_to(0.0, 0.0);
-        path.curve_to(0.0, 1.0, 1.0, 1.0, 1.0, 0.0);
-        path.close_path();
+    /// Get the inflection points of the path's cubic segments
+    ///
+    /// These are the points where a cubic's signed curvature changes sign.
+    /// Lines and quadratic curves have no inflections.
+    pub fn inflection_points(&self) -> Vec<Point2D> {
+        let path = lock(&self.path);
+        let mut points = Vec::new();
 
-        let kurbo_path = path.to_kurbo_path();
-        assert!(!kurbo_path.is_empty());
+        for seg in path.segments() {
+            if let PathSeg::Cubic(cubic) = seg {
+                for t in cubic.inflections() {
+                    if t > 0.0 && t < 1.0 {
+                        points.push(cubic.eval(t).into());
+                    }
+                }
+            }
+        }
+
+        points
     }
 
-    #[test]
-    fn test_quad_path() {
-        let path = BezierPath::new();
-        path.move_to(0.0, 0.0);
-        path.quad_to(0.5, 1.0, 1.0, 0.0);
-        path.close_path();
+    /// Get the cusp points of the path's cubic segments
+    ///
+    /// A cusp is where a cubic's first derivative vanishes, so its direction
+    /// is undefined there. Offsetting and stroking are unstable at cusps, so
+    /// splitting curves at these points first avoids artifacts.
+    pub fn cusp_points(&self) -> Vec<Point2D> {
+        let path = lock(&self.path);
+        let mut points = Vec::new();
 
-        let kurbo_path = path.to_kurbo_path();
-        assert!(!kurbo_path.is_empty());
+        for seg in path.segments() {
+            if let PathSeg::Cubic(cubic) = seg {
+                points.extend(cubic_cusps(&cubic).into_iter().map(|t| Point2D::from(cubic.eval(t))));
+            }
+        }
+
+        points
     }
 
-    #[test]
-    fn test_basic_operation() {
-        // Create a simple square using the new API
-        let square = BezierPath::new();
-        square.move_to(0.0, 0.0);
-        square.line_to(1.0, 0.0);
-        square.line_to(1.0, 1.0);
-        square.line_to(0.0, 1.0);
-        square.close_path();
+    /// Find subpaths that duplicate an earlier subpath of this path
+    ///
+    /// Two subpaths are duplicates when their segments match within
+    /// `tolerance`, whichever direction they run in and, for closed
+    /// subpaths, whichever vertex they start at. Returns the indices of the
+    /// later copies.
+    pub fn find_duplicate_subpaths(&self, tolerance: f64) -> Vec<u64> {
+        let subpaths = split_subpaths(&lock(&self.path));
+        duplicate_subpaths(&subpaths, tolerance)
+            .into_iter()
+            .map(|i| i as u64)
+            .collect()
+    }
 
-        // Create another square offset by 0.5
-        let offset_square = BezierPath::new();
-        offset_square.move_to(0.5, 0.5);
-        offset_square.line_to(1.5, 0.5);
-        offset_square.line_to(1.5, 1.5);
-        offset_square.line_to(0.5, 1.5);
-        offset_square.close_path();
+    /// Get a copy of the path without zero-length segments or redundant line vertices
+    ///
+    /// A segment whose points are all within `tolerance` of where it starts
+    /// is dropped, and so is the vertex between two lines if it is within
+    /// `tolerance` of the single line that could replace them, which merges
+    /// collinear runs. Closed subpaths stay closed and wrap around, so the
+    /// vertex at their start can be merged too. Subpaths left with nothing
+    /// drawn are dropped.
+    pub fn cleanup(&self, tolerance: f64) -> Arc<BezierPath> {
+        Arc::new(BezierPath::from_kurbo_path(cleanup_path(&lock(&self.path), tolerance)))
+    }
 
-        // Test union operation
-        let result = boolean_operation(
-            &square,
-            &offset_square,
-            BooleanOperation::Union,
-            FillRule::EvenOdd,
-        );
+    /// Remove subpaths that duplicate an earlier subpath of this path
+    ///
+    /// Uses the same matching as `find_duplicate_subpaths`, so a subpath and
+    /// its reverse count as duplicates since they enclose the same region.
+    /// The first occurrence of each is kept.
+    pub fn dedup_subpaths(&self, tolerance: f64) -> Arc<BezierPath> {
+        let subpaths = split_subpaths(&lock(&self.path));
+        let duplicates = duplicate_subpaths(&subpaths, tolerance);
 
-        assert!(result.is_ok());
-        let paths = result.unwrap();
-        assert!(!paths.paths.is_empty());
+        let mut result = BezPath::new();
+        for (i, subpath) in subpaths.into_iter().enumerate() {
+            if !duplicates.contains(&i) {
+                result.extend(subpath);
+            }
+        }
+        Arc::new(BezierPath::from_kurbo_path(result))
     }
 
-    #[test]
-    fn test_segment_extraction() {
+    /// Get the arc length of each subpath, including its closing edge if closed
+    ///
+    /// `accuracy` bounds the error of the arc length integration for curves.
+    pub fn subpath_perimeters(&self, accuracy: f64) -> Vec<f64> {
+        split_subpaths(&lock(&self.path))
+            .iter()
+            .map(|subpath| subpath.segments().map(|seg| seg.arclen(accuracy)).sum())
+            .collect()
+    }
+
+    /// Get the total arc length of the path, including closing edges
+    ///
+    /// This is the sum of `subpath_perimeters`; `accuracy` bounds the error
+    /// of the arc length integration for curves.
+    pub fn length(&self, accuracy: f64) -> f64 {
+        lock(&self.path).segments().map(|seg| seg.arclen(accuracy)).sum()
+    }
+
+    /// Get the "on" parts of a repeating dash pattern along the path
+    ///
+    /// `pattern` alternates dash and gap lengths, starting with a dash, and
+    /// repeats along each subpath by arc length, with closing edges
+    /// included; `offset` is how far into the pattern the path starts. The
+    /// result has one open subpath per dash, ready for `stroke_outline`. A
+    /// pattern that is empty, all zero, or has a negative or non-finite
+    /// length gives an unchanged copy of the path.
+    pub fn dash(&self, pattern: Vec<f64>, offset: f64) -> Arc<BezierPath> {
+        let path = self.to_kurbo_path();
+        let valid = pattern.iter().all(|length| length.is_finite() && *length >= 0.0);
+        if !valid || pattern.iter().all(|length| *length == 0.0) || !offset.is_finite() {
+            return Arc::new(BezierPath::from_kurbo_path(path));
+        }
+        let dashed = kurbo::dash(path.iter(), offset, &pattern).collect();
+        Arc::new(BezierPath::from_kurbo_path(dashed))
+    }
+
+    /// Get the fillable outline of the path stroked at `width`
+    ///
+    /// The outline is made of closed contours that may overlap each other,
+    /// as kurbo's `stroke` produces them, so fill it or use it as a boolean
+    /// operand under `FillRule::NonZero`. Returns an error if `width` isn't
+    /// positive and finite, or if the path has non-finite coordinates.
+    pub fn stroke_outline(
+        &self,
+        width: f64,
+        cap: LineCapKind,
+        join: LineJoinKind,
+    ) -> Result<Arc<BezierPath>, LineSweeperError> {
+        check_stroke_width(width)?;
+        let path = self.to_kurbo_path();
+        check_finite(&path)?;
+        let style = Stroke::new(width).with_caps(cap.into()).with_join(join.into());
+        let outline = kurbo::stroke(path, &style, &StrokeOpts::default(), width * STROKE_TOLERANCE);
+        Ok(Arc::new(BezierPath::from_kurbo_path(outline)))
+    }
+
+    /// Get the parallel curve of one segment, at a signed distance
+    ///
+    /// Segments are numbered as in `intersection_params`, with closing edges
+    /// counted. Positive distances offset to the left of the direction of
+    /// travel, which is outward for a clockwise contour in a y-up system,
+    /// and negative ones to the right. The result is an open path whose
+    /// curves stay within `accuracy` of the true offset. Where the distance
+    /// exceeds the curve's radius of curvature the true offset has a cusp,
+    /// which is only followed approximately. Returns `None` if there is no
+    /// segment at `segment_index`.
+    pub fn offset_segment(
+        &self,
+        segment_index: u64,
+        distance: f64,
+        accuracy: f64,
+    ) -> Option<Arc<BezierPath>> {
+        let index = usize::try_from(segment_index).ok()?;
+        let seg = lock(&self.path).segments().nth(index)?;
+        Some(Arc::new(BezierPath::from_kurbo_path(offset_seg(seg, distance, accuracy))))
+    }
+
+    /// Get the two rails of the path, offset by `half_width` to either side
+    ///
+    /// Each segment is offset as by `offset_segment`, and the pieces are
+    /// joined as a miter join would: outer corners are extended to a point,
+    /// or beveled where that point would lie further than four half widths
+    /// away, and lines at inner corners are cut back to where they cross.
+    /// Closed subpaths give closed rails. Returns an error if `half_width`
+    /// isn't positive and finite, or if the path has non-finite coordinates.
+    pub fn rails(&self, half_width: f64, accuracy: f64) -> Result<RailPair, LineSweeperError> {
+        if !(half_width.is_finite() && half_width > 0.0) {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "half width must be positive and finite, got {half_width}"
+            )));
+        }
+        let path = self.to_kurbo_path();
+        check_finite(&path)?;
+        let [left, right] = [half_width, -half_width]
+            .map(|distance| Arc::new(BezierPath::from_kurbo_path(rail(&path, distance, accuracy))));
+        Ok(RailPair { left, right })
+    }
+
+    /// Create a new path by passing every segment through `mapper`
+    ///
+    /// Returns an error if the rewritten path doesn't start with a `MoveTo`.
+    pub fn map_segments(
+        &self,
+        mapper: Box<dyn SegmentMapper>,
+    ) -> Result<Arc<BezierPath>, LineSweeperError> {
+        let segments = self.get_segments();
+        let path = path_from_segments(segments.into_iter().map(|segment| mapper.map(segment)))?;
+        Ok(Arc::new(BezierPath::from_kurbo_path(path)))
+    }
+
+    /// Check whether the path is a single convex subpath
+    ///
+    /// Curves are flattened to within `tolerance` first, and the subpath is
+    /// treated as closed. A vertex that lies within `tolerance` of the line
+    /// through its neighbours counts as straight rather than as a turn, so
+    /// flattened curves and near-collinear points don't break convexity.
+    /// Paths with no or several subpaths are not convex.
+    pub fn is_convex(&self, tolerance: f64) -> bool {
+        let polylines = flatten_subpaths(&lock(&self.path), tolerance);
+        let [polyline] = polylines.as_slice() else {
+            return false;
+        };
+        let mut points = polyline.points.clone();
+        points.dedup();
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        ring_is_convex(&points, tolerance)
+    }
+
+    /// Check whether the outline crosses or touches itself
+    ///
+    /// Every pair of segments is checked, closing edges and segments of
+    /// different subpaths included, except that consecutive segments may
+    /// share their common vertex. Paths with non-finite coordinates report
+    /// no intersections.
+    pub fn has_self_intersections(&self) -> bool {
+        let path = lock(&self.path);
+        check_finite(&path).is_ok() && self_intersects(&path)
+    }
+
+    /// Test whether each of `points` is inside the path under `fill_rule`
+    ///
+    /// The path is flattened once and every point is tested against the same
+    /// edges, so this is much faster than testing points one by one. Open
+    /// subpaths are treated as closed, as when filling. The result is in the
+    /// same order as `points`.
+    pub fn contains_points(&self, points: Vec<Point2D>, fill_rule: FillRule) -> Vec<bool> {
+        let path = lock(&self.path);
+        let bbox = path.bounding_box();
+        let tolerance = bbox.width().hypot(bbox.height()) * 1e-6;
+        let rings = flatten_subpaths(&path, tolerance);
+
+        points
+            .into_iter()
+            .map(|p| {
+                let p = Point::from(p);
+                let winding = rings.iter().map(|ring| ring_winding(&ring.points, p)).sum();
+                fill_rule.is_inside(winding)
+            })
+            .collect()
+    }
+
+    /// Get the unit direction in which the path leaves its first point
+    ///
+    /// Zero-length segments and coincident control points are skipped, so
+    /// this is the direction the path actually starts moving in. Returns
+    /// `None` if the path never moves.
+    pub fn start_tangent(&self) -> Option<Point2D> {
+        let path = lock(&self.path);
+        let tangent = path.segments().find_map(|seg| start_direction(&seg))?;
+        Some(Point2D {
+            x: tangent.x,
+            y: tangent.y,
+        })
+    }
+
+    /// Get the unit direction in which the path arrives at its last point
+    ///
+    /// For a closed path this is the direction of the closing edge. Returns
+    /// `None` if the path never moves.
+    pub fn end_tangent(&self) -> Option<Point2D> {
+        let path = lock(&self.path);
+        let segments: Vec<PathSeg> = path.segments().collect();
+        let tangent = segments
+            .iter()
+            .rev()
+            .find_map(|seg| start_direction(&seg.reverse()))?;
+        Some(Point2D {
+            x: -tangent.x,
+            y: -tangent.y,
+        })
+    }
+
+    /// Get the point a fraction `t` of the way along the path
+    ///
+    /// `t` is measured by arc length over all segments, closing edges
+    /// included, and is clamped to `[0, 1]`. Returns `None` for a path with
+    /// no segments.
+    pub fn point_at(&self, t: f64) -> Option<Point2D> {
+        let (seg, param) = locate_fraction(&lock(&self.path), t)?;
+        Some(Point2D::from(seg.eval(param)))
+    }
+
+    /// Get the unit direction of the path a fraction `t` of the way along it
+    ///
+    /// `t` is mapped to a point as in `point_at`. Where the curve has no
+    /// derivative, at a cusp or a coincident control point, the direction
+    /// it starts or finishes the segment in is used instead. Returns `None`
+    /// for a path with no segments or of zero length.
+    pub fn tangent_at(&self, t: f64) -> Option<Point2D> {
+        let (seg, param) = locate_fraction(&lock(&self.path), t)?;
+        let deriv = seg.to_cubic().deriv().eval(param).to_vec2();
+        let tangent = if deriv.hypot() > 0.0 {
+            deriv.normalize()
+        } else if param < 0.5 {
+            start_direction(&seg)?
+        } else {
+            -start_direction(&seg.reverse())?
+        };
+        Some(Point2D {
+            x: tangent.x,
+            y: tangent.y,
+        })
+    }
+
+    /// Find the point on the path closest to `(x, y)`
+    ///
+    /// Every segment is searched, closing edges included, and the nearest
+    /// point of all of them wins; `accuracy` bounds the error of the solve
+    /// on curves. Returns `None` for a path with no segments.
+    pub fn nearest_point(&self, x: f64, y: f64, accuracy: f64) -> Option<NearestResult> {
+        let point = Point::new(x, y);
+        let (seg, nearest) = lock(&self.path)
+            .segments()
+            .map(|seg| (seg, seg.nearest(point, accuracy)))
+            .min_by(|a, b| a.1.distance_sq.total_cmp(&b.1.distance_sq))?;
+        Some(NearestResult {
+            point: Point2D::from(seg.eval(nearest.t)),
+            distance: nearest.distance_sq.sqrt(),
+        })
+    }
+
+    /// Get the points on the path that reach furthest up, down, left and right
+    ///
+    /// These are actual points on the outline, including the extrema of
+    /// curves rather than just their endpoints. Up is towards positive y, as
+    /// for signed areas. Returns `None` for an empty path.
+    pub fn extreme_points(&self) -> Option<ExtremePoints> {
+        let path = lock(&self.path);
+        let mut candidates: Vec<Point> = path
+            .elements()
+            .iter()
+            .filter_map(|el| el.end_point())
+            .collect();
+        for seg in path.segments() {
+            candidates.extend(seg.extrema().into_iter().map(|t| seg.eval(t)));
+        }
+
+        let first = *candidates.first()?;
+        let mut extremes = [first; 4];
+        for p in candidates {
+            if p.y > extremes[0].y {
+                extremes[0] = p;
+            }
+            if p.y < extremes[1].y {
+                extremes[1] = p;
+            }
+            if p.x < extremes[2].x {
+                extremes[2] = p;
+            }
+            if p.x > extremes[3].x {
+                extremes[3] = p;
+            }
+        }
+        let [top, bottom, left, right] = extremes.map(Point2D::from);
+        Some(ExtremePoints {
+            top,
+            bottom,
+            left,
+            right,
+        })
+    }
+
+    /// Sample `n` points equally spaced by arc length around the first closed subpath
+    ///
+    /// Sampling starts at the subpath's start point and goes around in its
+    /// own direction, including the closing edge; the start point is not
+    /// repeated at the end. `accuracy` bounds the arc length error for
+    /// curves. Returns an empty list if `n` is less than 2 or the path has
+    /// no closed subpath.
+    pub fn resample_uniform(&self, n: u64, accuracy: f64) -> Vec<Point2D> {
+        if n < 2 {
+            return Vec::new();
+        }
+        let subpaths = split_subpaths(&lock(&self.path));
+        let Some(subpath) = subpaths
+            .iter()
+            .find(|subpath| subpath.elements().last() == Some(&PathEl::ClosePath))
+        else {
+            return Vec::new();
+        };
+
+        let segments: Vec<(PathSeg, f64)> = subpath
+            .segments()
+            .map(|seg| (seg, seg.arclen(accuracy)))
+            .collect();
+        let perimeter: f64 = segments.iter().map(|(_, length)| length).sum();
+        (0..n)
+            .map(|i| {
+                let s = perimeter * i as f64 / n as f64;
+                Point2D::from(point_at_arclen(&segments, s, accuracy))
+            })
+            .collect()
+    }
+
+    /// Get a descriptor of the path's shape for similarity matching
+    ///
+    /// The first closed subpath is resampled uniformly and the distance of
+    /// each sample from the samples' centroid is divided by the mean
+    /// distance. The magnitudes of the first `bins` Fourier coefficients of
+    /// that signature, skipping the constant term, are returned, so the
+    /// descriptor doesn't change with position, scale, rotation, or where
+    /// the outline starts. Compare descriptors by Euclidean distance.
+    /// Returns an empty list if the path has no closed subpath enclosing
+    /// anything. `bins` is capped at 256, since the cost grows with its
+    /// square.
+    pub fn shape_descriptor(&self, bins: u32) -> Vec<f64> {
+        let bins = bins.min(SHAPE_DESCRIPTOR_MAX_BINS);
+        if bins == 0 {
+            return Vec::new();
+        }
+        let bbox = lock(&self.path).bounding_box();
+        let accuracy = bbox.width().hypot(bbox.height()) * 1e-6;
+        let n = (4 * bins as usize).max(64);
+        let samples: Vec<Point> = self
+            .resample_uniform(n as u64, accuracy)
+            .into_iter()
+            .map(Point::from)
+            .collect();
+        if samples.is_empty() {
+            return Vec::new();
+        }
+
+        let sum = samples.iter().fold(kurbo::Vec2::ZERO, |sum, p| sum + p.to_vec2());
+        let centroid = (sum / n as f64).to_point();
+        let distances: Vec<f64> = samples.iter().map(|p| p.distance(centroid)).collect();
+        let mean = distances.iter().sum::<f64>() / n as f64;
+        if !(mean.is_finite() && mean > 0.0) {
+            return Vec::new();
+        }
+        (1..=bins as usize)
+            .map(|k| {
+                let (re, im) = distances.iter().enumerate().fold((0.0, 0.0), |(re, im), (i, d)| {
+                    let angle = std::f64::consts::TAU * (k * i) as f64 / n as f64;
+                    (re + d * angle.cos(), im - d * angle.sin())
+                });
+                re.hypot(im) / (mean * n as f64)
+            })
+            .collect()
+    }
+
+    /// Set the fill rule used by the methods that don't take one
+    pub fn set_default_fill_rule(&self, rule: FillRule) {
+        *lock(&self.default_fill_rule) = rule;
+    }
+
+    /// Get the fill rule used by the methods that don't take one
+    ///
+    /// This is `FillRule::NonZero` unless changed with `set_default_fill_rule`.
+    pub fn default_fill_rule(&self) -> FillRule {
+        *lock(&self.default_fill_rule)
+    }
+
+    /// Test whether a point is inside the path under its default fill rule
+    pub fn contains_default(&self, x: f64, y: f64) -> bool {
+        let rule = self.default_fill_rule();
+        self.contains_points(vec![Point2D { x, y }], rule)[0]
+    }
+
+    /// Get the fraction of a rectangle covered by the filled path
+    ///
+    /// The path is intersected with the rectangle and the areas of the
+    /// resulting regions (minus their holes) are divided by the rectangle's
+    /// area, giving a value between 0 and 1. Returns an error if the
+    /// rectangle is empty.
+    pub fn coverage_in_rect(
+        &self,
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        fill_rule: FillRule,
+    ) -> Result<f64, LineSweeperError> {
+        let rect = kurbo::Rect::new(x, y, x + w, y + h).abs();
+        if !rect.is_finite() || rect.is_zero_area() {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "rectangle must have a positive area, got {w} x {h}"
+            )));
+        }
+        let clip = BezierPath::from_kurbo_path(rect.to_path(0.1));
+        let result = boolean_operation(self, &clip, BooleanOperation::Intersection, fill_rule)?;
+        Ok((result_area(&result) / rect.area()).clamp(0.0, 1.0))
+    }
+
+    /// Reserve room for at least `additional_elements` more elements
+    ///
+    /// Call this before building a large path one element at a time to avoid
+    /// repeated reallocation. kurbo doesn't expose `reserve` on its paths, so
+    /// the existing elements are moved into a larger buffer once. Returns an
+    /// `InvalidArgument` error, leaving the path unchanged, if that much
+    /// memory can't be allocated.
+    pub fn reserve(&self, additional_elements: u64) -> Result<(), LineSweeperError> {
+        let mut path = lock(&self.path);
+        let additional = usize::try_from(additional_elements).unwrap_or(usize::MAX);
+        let capacity = path.elements().len().saturating_add(additional);
+        let mut elements = Vec::new();
+        elements.try_reserve(capacity).map_err(|_| {
+            LineSweeperError::InvalidArgument(format!(
+                "cannot reserve room for {additional_elements} more elements"
+            ))
+        })?;
+        // Empty, so kurbo's check that a path starts with a MoveTo holds
+        let mut reserved = BezPath::from_vec(elements);
+        reserved.extend(path.iter());
+        *path = reserved;
+        Ok(())
+    }
+
+    /// Get the path's segments grouped by subpath
+    ///
+    /// A new group starts at each `MoveTo`, so every group but possibly the
+    /// first starts with one.
+    pub fn segments_by_subpath(&self) -> Vec<Vec<PathSegment>> {
+        split_subpaths(&lock(&self.path))
+            .iter()
+            .map(|subpath| subpath.iter().map(PathSegment::from).collect())
+            .collect()
+    }
+
+    /// Apply an affine transformation to one subpath, in place
+    ///
+    /// The coefficients are in the same order as kurbo's `Affine`: a point
+    /// (x, y) maps to (a·x + c·y + e, b·x + d·y + f). Subpaths are numbered
+    /// as in `segments_by_subpath`; an out-of-range index leaves the path
+    /// unchanged.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transform_subpath(
+        &self,
+        subpath_index: u64,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) {
+        let affine = Affine::new([a, b, c, d, e, f]);
+        let mut path = lock(&self.path);
+        let mut index = None;
+        for el in path.elements_mut() {
+            if matches!(el, PathEl::MoveTo(_)) || index.is_none() {
+                index = Some(index.map_or(0, |i: u64| i + 1));
+            }
+            if index == Some(subpath_index) {
+                *el = affine * *el;
+            }
+        }
+    }
+
+    /// Intersect the filled path with a circle
+    ///
+    /// The circle is approximated by cubic Béziers to within a small fraction
+    /// of its radius, and since the sweep works on curves directly, result
+    /// edges along the circle stay curves. Returns an error if `r` isn't
+    /// positive.
+    pub fn clip_to_circle(
+        &self,
+        cx: f64,
+        cy: f64,
+        r: f64,
+        fill_rule: FillRule,
+    ) -> Result<BooleanOperationResult, LineSweeperError> {
+        if !(r.is_finite() && r > 0.0) {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "radius must be positive and finite, got {r}"
+            )));
+        }
+        let circle = kurbo::Circle::new((cx, cy), r).to_path(r * 1e-6);
+        let clip = BezierPath::from_kurbo_path(circle);
+        boolean_operation(self, &clip, BooleanOperation::Intersection, fill_rule)
+    }
+
+    /// Estimate the width of a thin filled shape, such as an outlined stroke
+    ///
+    /// The outline is flattened to within `accuracy`, and at evenly spaced
+    /// points along it the distance across the shape is measured
+    /// perpendicular to the boundary; the median of those distances is
+    /// returned. Returns `None` if the shape is not ribbon-like, that is if
+    /// its area is too large for its perimeter.
+    pub fn estimate_ribbon_width(&self, accuracy: f64) -> Option<f64> {
+        let rings: Vec<Vec<Point>> = flatten_subpaths(&lock(&self.path), accuracy)
+            .into_iter()
+            .map(|polyline| polyline.points)
+            .filter(|ring| ring.len() >= 3)
+            .collect();
+        let parents = ring_parents(&rings);
+        let areas: Vec<f64> = rings.iter().map(|ring| ring_signed_area(ring).abs()).collect();
+        let area = nested_area(&areas, &parents);
+        let perimeter: f64 = rings.iter().map(|ring| ring_perimeter(ring)).sum();
+        let roundness = 4.0 * std::f64::consts::PI * area / (perimeter * perimeter);
+        if perimeter == 0.0 || roundness > RIBBON_MAX_ROUNDNESS {
+            return None;
+        }
+
+        let spacing = perimeter / 512.0;
+        let mut widths = Vec::new();
+        for (i, ring) in rings.iter().enumerate() {
+            // The filled side is on the left of counterclockwise outer rings
+            let counterclockwise = ring_signed_area(ring) > 0.0;
+            let fill_on_left = counterclockwise == nesting_depth(&parents, i).is_multiple_of(2);
+            for j in 0..ring.len() {
+                let (a, b) = (ring[j], ring[(j + 1) % ring.len()]);
+                let edge = b - a;
+                let length = edge.hypot();
+                if length == 0.0 {
+                    continue;
+                }
+                let left = edge.turn_90() / length;
+                let normal = if fill_on_left { left } else { -left };
+                let samples = (length / spacing).ceil().max(1.0) as usize;
+                for k in 0..samples {
+                    let p = a.lerp(b, (k as f64 + 0.5) / samples as f64);
+                    widths.extend(ray_distance(&rings, p, normal, (i, j)));
+                }
+            }
+        }
+
+        widths.sort_by(f64::total_cmp);
+        widths.get(widths.len() / 2).copied()
+    }
+
+    /// Classify whether filling the path could look unexpected
+    ///
+    /// Crossing edges are reported first, as `SelfOverlapping`; otherwise
+    /// the orientation of nested contours is checked. Open subpaths are
+    /// treated as closed, as when filling.
+    pub fn fill_ambiguity(&self) -> FillAmbiguity {
+        let path = lock(&self.path);
+        let bbox = path.bounding_box();
+        let tolerance = bbox.width().hypot(bbox.height()) * 1e-6;
+        let rings: Vec<Vec<Point>> = flatten_subpaths(&path, tolerance)
+            .into_iter()
+            .map(|polyline| polyline.points)
+            .filter(|ring| ring.len() >= 3)
+            .collect();
+
+        if rings_cross(&rings) {
+            return FillAmbiguity::SelfOverlapping;
+        }
+        let parents = ring_parents(&rings);
+        let same_as_parent = rings.iter().zip(&parents).any(|(ring, parent)| {
+            parent.is_some_and(|parent| {
+                ring_signed_area(ring) * ring_signed_area(&rings[parent]) > 0.0
+            })
+        });
+        if same_as_parent {
+            FillAmbiguity::MixedWinding
+        } else {
+            FillAmbiguity::Clear
+        }
+    }
+
+    /// Encode the path in a compact binary format
+    ///
+    /// Each element is a tag byte (0 = MoveTo, 1 = LineTo, 2 = QuadTo,
+    /// 3 = CurveTo, 4 = ClosePath) followed by its coordinates as
+    /// little-endian `f64`s, so the data reads the same on every platform
+    /// and round-trips bit for bit through `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_path(&lock(&self.path))
+    }
+
+    /// Find the smallest regular polygon with `sides` sides that contains the path
+    ///
+    /// The polygon may have any rotation and position. Curves are flattened
+    /// finely and only the convex hull of the result matters. The search is
+    /// numerical, sampling rotations and then refining the best one.
+    /// Returns `None` if `sides` is less than 3 or the path is empty.
+    pub fn enclosing_regular_polygon(&self, sides: u32) -> Option<Arc<BezierPath>> {
+        if sides < 3 {
+            return None;
+        }
+        let hull = convex_hull(&lock(&self.path));
+        if hull.is_empty() {
+            return None;
+        }
+
+        // Rotations repeat every 2π/n, so search one period
+        let period = std::f64::consts::TAU / sides as f64;
+        let samples = 90;
+        let (mut best_theta, mut best) = (0.0, fit_regular_polygon(&hull, sides, 0.0));
+        for i in 1..samples {
+            let theta = period * i as f64 / samples as f64;
+            let fit = fit_regular_polygon(&hull, sides, theta);
+            if fit.0 < best.0 {
+                (best_theta, best) = (theta, fit);
+            }
+        }
+        let step = period / samples as f64;
+        let theta = golden_section_min(best_theta - step, best_theta + step, |theta| {
+            fit_regular_polygon(&hull, sides, theta).0
+        });
+        let fit = fit_regular_polygon(&hull, sides, theta);
+        if fit.0 < best.0 {
+            (best_theta, best) = (theta, fit);
+        }
+
+        let (apothem, center) = best;
+        let circumradius = apothem / (period / 2.0).cos();
+        let mut path = BezPath::new();
+        for k in 0..sides {
+            let angle = best_theta + period * (k as f64 + 0.5);
+            let vertex = center + kurbo::Vec2::from_angle(angle) * circumradius;
+            if k == 0 {
+                path.move_to(vertex);
+            } else {
+                path.line_to(vertex);
+            }
+        }
+        path.close_path();
+        Some(Arc::new(BezierPath::from_kurbo_path(path)))
+    }
+
+    /// Snap nearly horizontal and vertical lines onto the pixel grid at `scale`
+    ///
+    /// A line (including a closing edge) whose ends differ by less than half
+    /// a pixel in y, or in x, has that coordinate of both ends moved to the
+    /// pixel boundary nearest their average, `round(coord * scale) / scale`.
+    /// Other lines and curves keep their shape, though a curve's end moves
+    /// with any snapped line it shares a vertex with.
+    pub fn pixel_snap(&self, scale: f64) -> Arc<BezierPath> {
+        let path = self.to_kurbo_path();
+        let mut result = BezPath::new();
+
+        for subpath in split_subpaths(&path) {
+            let mut elements = subpath.elements().to_vec();
+            let mut lines = Vec::new();
+            for (i, el) in elements.iter().enumerate().skip(1) {
+                match el {
+                    PathEl::LineTo(_) => lines.push((i - 1, i)),
+                    PathEl::ClosePath => lines.push((i - 1, 0)),
+                    _ => {}
+                }
+            }
+
+            let ends: Vec<Option<Point>> = elements.iter().map(|el| el.end_point()).collect();
+            let mut snapped = ends.clone();
+            let snap = |a: f64, b: f64| ((a + b) * 0.5 * scale).round() / scale;
+            for (i, j) in lines {
+                let (Some(a), Some(b)) = (ends[i], ends[j]) else {
+                    continue;
+                };
+                if ((a.y - b.y) * scale).abs() < 0.5 {
+                    let y = snap(a.y, b.y);
+                    for k in [i, j] {
+                        snapped[k] = snapped[k].map(|p| Point::new(p.x, y));
+                    }
+                }
+                if ((a.x - b.x) * scale).abs() < 0.5 {
+                    let x = snap(a.x, b.x);
+                    for k in [i, j] {
+                        snapped[k] = snapped[k].map(|p| Point::new(x, p.y));
+                    }
+                }
+            }
+
+            for (el, p) in elements.iter_mut().zip(snapped) {
+                if let Some(p) = p {
+                    *el = with_end_point(*el, p);
+                }
+            }
+            result.extend(elements);
+        }
+
+        Arc::new(BezierPath::from_kurbo_path(result))
+    }
+
+    /// Get a concave outline hugging the path, at a detail level of `alpha`
+    ///
+    /// The outline is a closed polygon through points sampled along the
+    /// finely flattened path, made by cutting into the convex hull wherever
+    /// an edge is longer than `alpha`. Larger values approach the convex hull
+    /// and smaller ones follow concavities more tightly. A non-positive or
+    /// non-finite `alpha` gives the convex hull itself.
+    pub fn concave_hull(&self, alpha: f64) -> Arc<BezierPath> {
+        let hull = Polyline {
+            points: concave_hull(&lock(&self.path), alpha),
+            closed: true,
+        };
+        Arc::new(BezierPath::from_kurbo_path(polylines_to_path(&[hull])))
+    }
+
+    /// Get the regions inside the path's convex hull but outside the path
+    ///
+    /// Each resulting contour is one dent in the shape. A convex path has
+    /// none.
+    pub fn concavity_regions(
+        &self,
+        fill_rule: FillRule,
+    ) -> Result<BooleanOperationResult, LineSweeperError> {
+        let hull = Polyline {
+            points: convex_hull(&lock(&self.path)),
+            closed: true,
+        };
+        let hull = BezierPath::from_kurbo_path(polylines_to_path(&[hull]));
+        boolean_operation(&hull, self, BooleanOperation::Difference, fill_rule)
+    }
+
+    /// Round the corners between straight lines, choosing convex, concave or both
+    ///
+    /// A corner is convex when the filled side of the path is on the inside
+    /// of the turn, judged from each subpath's orientation and whether it is
+    /// a hole. Each chosen corner is replaced by a circular arc of `radius`,
+    /// made smaller where needed so neighbouring arcs don't overlap. Corners
+    /// next to curves are left sharp.
+    pub fn round_corners_selective(
+        &self,
+        radius: f64,
+        convex: bool,
+        concave: bool,
+    ) -> Arc<BezierPath> {
+        let subpaths = split_subpaths(&lock(&self.path));
+        let parents = subpath_parents(&subpaths);
+        let mut result = BezPath::new();
+
+        for (i, subpath) in subpaths.iter().enumerate() {
+            let is_hole = !nesting_depth(&parents, i).is_multiple_of(2);
+            let fill_on_left = (subpath.area() > 0.0) != is_hole;
+            let should_round = |left_turn: bool| {
+                if left_turn == fill_on_left {
+                    convex
+                } else {
+                    concave
+                }
+            };
+            round_subpath_corners(subpath, radius, should_round, &mut result);
+        }
+
+        Arc::new(BezierPath::from_kurbo_path(result))
+    }
+
+    /// Split the path at every point where it crosses or touches itself
+    ///
+    /// Returns the pieces between those points as open paths, so that no
+    /// two pieces cross. Pieces of a subpath run in the subpath's own
+    /// direction, and a closed subpath that crosses nothing comes back
+    /// as a single piece that ends where it starts. Returns an error if the
+    /// path has non-finite coordinates.
+    pub fn planar_edges(&self) -> Result<Vec<Arc<BezierPath>>, LineSweeperError> {
+        let path = self.to_kurbo_path();
+        check_finite(&path)?;
+        let bbox = path.bounding_box();
+        let tolerance = bbox.width().hypot(bbox.height()) * 1e-9;
+
+        let subpaths: Vec<Vec<PathSeg>> = split_subpaths(&path)
+            .iter()
+            .map(|subpath| subpath.segments().collect())
+            .collect();
+        let splits = self_intersection_params(&subpaths, tolerance);
+
+        let mut edges = Vec::new();
+        for (segments, splits) in subpaths.iter().zip(&splits) {
+            edges.extend(split_at_params(segments, splits));
+        }
+        Ok(edges
+            .into_iter()
+            .map(|edge| Arc::new(BezierPath::from_kurbo_path(edge)))
+            .collect())
+    }
+
+    /// Fill gaps and pinholes narrower than about twice `radius`
+    ///
+    /// This is a morphological close: the filled region is grown by `radius`
+    /// and then shrunk by the same amount, which roughly preserves its
+    /// outline elsewhere (convex corners stay sharp, concave corners are
+    /// rounded). `accuracy` bounds the error of the offset curves. Returns
+    /// an error if `radius` is negative.
+    pub fn morphological_close(
+        &self,
+        radius: f64,
+        fill_rule: FillRule,
+        accuracy: f64,
+    ) -> Result<Arc<BezierPath>, LineSweeperError> {
+        check_radius(radius)?;
+        let region = filled_region(self, fill_rule)?;
+        let grown = offset_region(&region, radius, accuracy)?;
+        let closed = offset_region(&grown, -radius, accuracy)?;
+        Ok(Arc::new(BezierPath::from_kurbo_path(closed)))
+    }
+
+    /// Remove spikes, protrusions and bridges narrower than about twice `radius`
+    ///
+    /// This is a morphological open, the counterpart of
+    /// `morphological_close`: the filled region is shrunk by `radius` and
+    /// then grown back. Holes take part like any other boundary, so a thin
+    /// strip between a hole and the outside, or between two holes, is cut
+    /// through. Returns an error if `radius` is negative.
+    pub fn morphological_open(
+        &self,
+        radius: f64,
+        fill_rule: FillRule,
+        accuracy: f64,
+    ) -> Result<Arc<BezierPath>, LineSweeperError> {
+        check_radius(radius)?;
+        let region = filled_region(self, fill_rule)?;
+        let shrunk = offset_region(&region, -radius, accuracy)?;
+        let opened = offset_region(&shrunk, radius, accuracy)?;
+        Ok(Arc::new(BezierPath::from_kurbo_path(opened)))
+    }
+
+    /// Grow the filled region by `distance`, or shrink it if `distance` is negative
+    ///
+    /// The path is filled under its default fill rule and its boundary is
+    /// offset with round corners, as in `morphological_close`, so the result
+    /// is a set of non-overlapping contours ready to fill. `accuracy` bounds
+    /// the error of the offset curves. Returns an error if `distance` isn't
+    /// finite or the path has non-finite coordinates.
+    pub fn offset(
+        &self,
+        distance: f64,
+        accuracy: f64,
+    ) -> Result<Arc<BezierPath>, LineSweeperError> {
+        if !distance.is_finite() {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "offset distance must be finite, got {distance}"
+            )));
+        }
+        let region = filled_region(self, self.default_fill_rule())?;
+        let offset = offset_region(&region, distance, accuracy)?;
+        Ok(Arc::new(BezierPath::from_kurbo_path(offset)))
+    }
+
+    /// Get how circle-like the path is, from 0 to 1
+    ///
+    /// This is the isoperimetric quotient 4π·area / perimeter², which is 1
+    /// for a circle and approaches 0 for thin slivers. Holes are subtracted
+    /// from the area, and their outlines count towards the perimeter.
+    /// `accuracy` bounds the error of the arc length integration for curves.
+    /// Empty paths and paths with no perimeter give 0.
+    pub fn roundness(&self, accuracy: f64) -> f64 {
+        let perimeter: f64 = self.subpath_perimeters(accuracy).iter().sum();
+        if perimeter <= 0.0 {
+            return 0.0;
+        }
+        let area = filled_area(&split_subpaths(&lock(&self.path)));
+        (4.0 * std::f64::consts::PI * area / (perimeter * perimeter)).clamp(0.0, 1.0)
+    }
+
+    /// Clamp every coordinate of the path into a rectangle
+    ///
+    /// Each on- and off-curve point is moved independently to the nearest
+    /// point of the rectangle at (`x`, `y`) with size `w` × `h`. This is not
+    /// geometric clipping: parts of the shape outside the rectangle are
+    /// flattened onto its edges, distorting the shape, but the result is
+    /// guaranteed to stay in bounds.
+    pub fn clamp_to_bounds(&self, x: f64, y: f64, w: f64, h: f64) -> Arc<BezierPath> {
+        let rect = kurbo::Rect::new(x, y, x + w, y + h).abs();
+        let clamp = |p: Point| Point::new(p.x.clamp(rect.x0, rect.x1), p.y.clamp(rect.y0, rect.y1));
+        let path = lock(&self.path);
+        let clamped = path.elements().iter().map(|el| map_points(*el, clamp)).collect();
+        Arc::new(BezierPath::from_kurbo_path(clamped))
+    }
+
+    /// Check whether any cubic segment of the path has a loop or a cusp
+    ///
+    /// A cubic loops when it crosses itself between its end points, and has
+    /// a cusp where its direction is undefined (see `cusp_points`); a handle
+    /// retracted onto an end point doesn't count. Lines and quadratic curves
+    /// never loop.
+    pub fn has_curve_loops(&self) -> bool {
+        let path = lock(&self.path);
+        let has_loops = path.segments().any(|seg| match seg {
+            PathSeg::Cubic(cubic) => {
+                cubic_has_loop(&cubic)
+                    || cubic_cusps(&cubic).iter().any(|&t| t > 1e-9 && t < 1.0 - 1e-9)
+            }
+            _ => false,
+        });
+        has_loops
+    }
+
+    /// Combine some of the path's subpaths with a boolean operation
+    ///
+    /// The selected subpaths are folded together in the order given, so
+    /// `Difference` subtracts the others from the first and `Intersection`
+    /// keeps what they all share. The resulting contours take the place of
+    /// the first selected subpath in path order; unselected subpaths are
+    /// kept exactly as they are. Subpaths are numbered as in
+    /// `segments_by_subpath`. Returns an error if no subpath is selected or
+    /// an index is out of range.
+    pub fn boolean_on_subpaths(
+        &self,
+        subpath_indices: Vec<u64>,
+        operation: BooleanOperation,
+        fill_rule: FillRule,
+    ) -> Result<Arc<BezierPath>, LineSweeperError> {
+        let subpaths = split_subpaths(&lock(&self.path));
+        let mut selected: Vec<usize> = Vec::new();
+        for index in subpath_indices {
+            // Checked rather than cast, so a large index can't wrap into range on 32-bit targets
+            let Some(index) = usize::try_from(index).ok().filter(|&i| i < subpaths.len()) else {
+                return Err(LineSweeperError::InvalidArgument(format!(
+                    "subpath index {index} is out of range for {} subpaths",
+                    subpaths.len()
+                )));
+            };
+            if !selected.contains(&index) {
+                selected.push(index);
+            }
+        }
+        let Some(&first) = selected.first() else {
+            return Err(LineSweeperError::InvalidArgument(
+                "no subpaths selected".to_string(),
+            ));
+        };
+
+        let mut combined = BezierPath::from_kurbo_path(subpaths[first].clone());
+        for &index in &selected[1..] {
+            let next = BezierPath::from_kurbo_path(subpaths[index].clone());
+            let result = boolean_operation(&combined, &next, operation, fill_rule)?;
+            combined = BezierPath::from_kurbo_path(
+                result.paths.iter().flat_map(|p| p.to_kurbo_path()).collect(),
+            );
+        }
+
+        let insert_at = selected.iter().copied().min().unwrap_or(first);
+        let mut path = BezPath::new();
+        for (i, subpath) in subpaths.into_iter().enumerate() {
+            if i == insert_at {
+                path.extend(combined.to_kurbo_path());
+            } else if !selected.contains(&i) {
+                path.extend(subpath);
+            }
+        }
+        Ok(Arc::new(BezierPath::from_kurbo_path(path)))
+    }
+
+    /// Serialize the path as an SVG path data string, for a `d` attribute
+    ///
+    /// Commands are absolute `M`, `L`, `Q`, `C` and `Z`, separated by spaces.
+    /// With `decimal_places`, each coordinate is rounded to that many digits
+    /// after the decimal point; otherwise coordinates are written at full
+    /// precision, so `from_svg` parses them back exactly.
+    pub fn to_svg(&self, decimal_places: Option<u8>) -> String {
+        svg_path_data(&lock(&self.path), decimal_places, false)
+    }
+
+    /// Draw the filled path as text, for a quick look in a terminal or log
+    ///
+    /// The bounding box is divided into `width` × `height` cells, and each
+    /// cell is `█` if its center is inside the path under `fill_rule`, or a
+    /// space otherwise. Rows are separated by newlines, with the largest y
+    /// at the top. Gives an empty string if either dimension is zero.
+    pub fn to_ascii_preview(&self, width: u32, height: u32, fill_rule: FillRule) -> String {
+        if width == 0 || height == 0 {
+            return String::new();
+        }
+        let bbox = lock(&self.path).bounding_box();
+        let (cell_w, cell_h) = (bbox.width() / width as f64, bbox.height() / height as f64);
+        let centers = (0..height).rev().flat_map(|row| {
+            (0..width).map(move |col| Point2D {
+                x: bbox.x0 + (col as f64 + 0.5) * cell_w,
+                y: bbox.y0 + (row as f64 + 0.5) * cell_h,
+            })
+        });
+
+        let inside = self.contains_points(centers.collect(), fill_rule);
+        inside
+            .chunks(width as usize)
+            .map(|row| row.iter().map(|&filled| if filled { '█' } else { ' ' }).collect())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Get the winding number of the path at the center of each cell of a grid
+    ///
+    /// The affine `[a, b, c, d, e, f]` maps grid coordinates to path
+    /// coordinates, in the same order as `apply_transform`; the cell in row
+    /// `r` and column `c` is sampled at (c + 0.5, r + 0.5) before mapping.
+    /// Windings are returned row by row, `width` per row, and are computed
+    /// as in `contains_points`, so applying a fill rule to them gives its
+    /// results.
+    #[allow(clippy::too_many_arguments)]
+    pub fn winding_image(
+        &self,
+        width: u32,
+        height: u32,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) -> Vec<i32> {
+        let path = lock(&self.path);
+        let bbox = path.bounding_box();
+        let tolerance = bbox.width().hypot(bbox.height()) * 1e-6;
+        let rings = flatten_subpaths(&path, tolerance);
+        let affine = Affine::new([a, b, c, d, e, f]);
+
+        (0..height)
+            .flat_map(|row| (0..width).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                let p = affine * Point::new(col as f64 + 0.5, row as f64 + 0.5);
+                rings.iter().map(|ring| ring_winding(&ring.points, p)).sum()
+            })
+            .collect()
+    }
+
+    /// Pack the path into element tags and a coordinate list
+    ///
+    /// This is the inverse of `from_flat_commands`, with the same tags and
+    /// coordinate layout.
+    pub fn to_flat_commands(&self) -> FlatPath {
+        flat_commands(&lock(&self.path))
+    }
+
+    /// Get the smallest rectangle containing the path
+    ///
+    /// This bounds the curves themselves, not their control points, so a
+    /// curve's bulge is included but its handles are not. Returns `None` for
+    /// an empty path.
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let path = lock(&self.path);
+        if path.elements().is_empty() {
+            return None;
+        }
+        Some(path.bounding_box().into())
+    }
+
+    /// Check that the path survives a round trip through SVG path data
+    ///
+    /// The path is written with `to_svg` at full precision and parsed back
+    /// with `from_svg`, and the result must have the same elements with
+    /// every point within `epsilon` of the original.
+    pub fn svg_roundtrip_ok(&self, epsilon: f64) -> bool {
+        let path = self.to_kurbo_path();
+        match BezPath::from_svg(&svg_path_data(&path, None, false)) {
+            Ok(parsed) => elements_approx_eq(&path, &parsed, epsilon),
+            Err(_) => false,
+        }
+    }
+
+    /// Check whether two paths have the same elements, to within `epsilon`
+    ///
+    /// The paths must have the same commands in the same order, and each
+    /// coordinate of each point may differ by at most `epsilon`. Paths that
+    /// trace the same shape differently, such as from another start point
+    /// or in the other direction, are not equal.
+    pub fn approx_eq(&self, other: &BezierPath, epsilon: f64) -> bool {
+        let (a, b) = (self.to_kurbo_path(), other.to_kurbo_path());
+        elements_match(&a, &b, |p, q| (p.x - q.x).abs() <= epsilon && (p.y - q.y).abs() <= epsilon)
+    }
+
+    /// Apply the affine `[a, b, c, d, e, f]` to the path, in place
+    ///
+    /// The coefficients are in the same order as kurbo's `Affine`: a point
+    /// (x, y) maps to (a·x + c·y + e, b·x + d·y + f). Control points are
+    /// transformed along with on-curve points.
+    pub fn apply_transform(&self, a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) {
+        let affine = Affine::new([a, b, c, d, e, f]);
+        lock(&self.path).apply_affine(affine);
+    }
+
+    /// Get a copy of the path with the affine `[a, b, c, d, e, f]` applied
+    ///
+    /// Like `apply_transform`, but leaves this path unchanged.
+    pub fn transformed(&self, a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Arc<BezierPath> {
+        let mut path = self.to_kurbo_path();
+        path.apply_affine(Affine::new([a, b, c, d, e, f]));
+        Arc::new(BezierPath::from_kurbo_path(path))
+    }
+
+    /// Move the path by (`dx`, `dy`), in place
+    pub fn translate(&self, dx: f64, dy: f64) {
+        lock(&self.path).apply_affine(Affine::translate((dx, dy)));
+    }
+
+    /// Scale the path about the origin, in place
+    ///
+    /// A negative factor mirrors the path across that axis, which reverses
+    /// the orientation of its contours unless both factors are negative.
+    pub fn scale(&self, sx: f64, sy: f64) {
+        lock(&self.path).apply_affine(Affine::scale_non_uniform(sx, sy));
+    }
+
+    /// Scale the path about the pivot (`cx`, `cy`), in place
+    ///
+    /// The pivot stays fixed, so scaling about the centroid or the center of
+    /// the bounding box resizes the shape in place.
+    pub fn scale_about(&self, sx: f64, sy: f64, cx: f64, cy: f64) {
+        let pivot = kurbo::Vec2::new(cx, cy);
+        let scale = Affine::scale_non_uniform(sx, sy);
+        let affine = Affine::translate(pivot) * scale * Affine::translate(-pivot);
+        lock(&self.path).apply_affine(affine);
+    }
+
+    /// Get a copy of the path rotated by `radians` about a pivot point
+    ///
+    /// Positive angles rotate counterclockwise in a y-up coordinate system.
+    /// The pivot stays fixed, so rotating about the center of the bounding
+    /// box spins the shape in place.
+    pub fn rotate_about(&self, pivot_x: f64, pivot_y: f64, radians: f64) -> Arc<BezierPath> {
+        let mut path = self.to_kurbo_path();
+        path.apply_affine(Affine::rotate_about(radians, Point::new(pivot_x, pivot_y)));
+        Arc::new(BezierPath::from_kurbo_path(path))
+    }
+
+    /// Serialize the path as SVG path data with relative commands
+    ///
+    /// Like `to_svg`, but with `m`, `l`, `q`, `c` and `z` commands whose
+    /// points are offsets from the current point, which usually gives a
+    /// shorter string. Coordinates are rounded to `decimal_places` digits
+    /// after the decimal point, and rounding errors don't build up along the
+    /// path.
+    pub fn to_svg_relative(&self, decimal_places: u8) -> String {
+        svg_path_data(&lock(&self.path), Some(decimal_places), true)
+    }
+
+    /// Get the area covered by exactly one of this path and `other`
+    ///
+    /// This is the area of their symmetric difference, with both filled
+    /// under `fill_rule`: zero for identical shapes, and growing as they
+    /// drift apart.
+    pub fn xor_area(
+        &self,
+        other: &BezierPath,
+        fill_rule: FillRule,
+    ) -> Result<f64, LineSweeperError> {
+        let result = boolean_operation(self, other, BooleanOperation::Xor, fill_rule)?;
+        Ok(result_area(&result))
+    }
+
+    /// Get the absolute value of `signed_area`
+    pub fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+
+    /// Get the signed area enclosed by the path, integrated exactly over curves
+    ///
+    /// Counterclockwise subpaths count as positive and clockwise ones as
+    /// negative, and the areas of all subpaths are summed, so a hole wound
+    /// opposite to its outer contour subtracts from it. Open subpaths are
+    /// treated as closed.
+    pub fn signed_area(&self) -> f64 {
+        lock(&self.path).area()
+    }
+
+    /// Get a copy of the path with every curve replaced by lines
+    ///
+    /// `method` picks how each curve is divided; lines and subpath structure
+    /// are kept as they are. A tolerance or length that isn't positive
+    /// replaces each curve with a single line, as does a count of zero. A
+    /// curve is never split into more than 4096 lines, however large the
+    /// count or small the length.
+    pub fn flatten_with(&self, method: FlattenMethod) -> Arc<BezierPath> {
+        let path = self.to_kurbo_path();
+        let flattened = match method {
+            FlattenMethod::AdaptiveTolerance { tolerance } if tolerance > 0.0 => {
+                let mut flattened = BezPath::new();
+                kurbo::flatten(path.iter(), tolerance, |el| flattened.push(el));
+                flattened
+            }
+            FlattenMethod::UniformCount { count } => {
+                split_curves(&path, |_| count.max(1) as usize, None)
+            }
+            FlattenMethod::UniformLength { length } if length > 0.0 => {
+                // The piece limit keeps lengths far below the path's size
+                // from being honored, so don't measure arc length for them
+                let bbox = path.bounding_box();
+                let shortest = bbox.width().hypot(bbox.height()) / SPLIT_CURVES_MAX_PIECES as f64;
+                let accuracy = length.max(shortest) * 1e-3;
+                let pieces =
+                    |seg: &PathSeg| (seg.arclen(accuracy) / length).ceil().max(1.0) as usize;
+                split_curves(&path, pieces, Some(accuracy))
+            }
+            _ => split_curves(&path, |_| 1, None),
+        };
+        Arc::new(BezierPath::from_kurbo_path(flattened))
+    }
+
+    /// Test whether a point is inside the path under `fill_rule`
+    ///
+    /// The winding number is computed on the curves directly, without
+    /// flattening. Open subpaths are treated as closed, as when filling.
+    /// Points exactly on the outline may be classified either way.
+    pub fn contains(&self, x: f64, y: f64, fill_rule: FillRule) -> bool {
+        fill_rule.is_inside(self.winding(x, y))
+    }
+
+    /// Get the winding number of the path around a point
+    ///
+    /// This is the sum over all subpaths of how many times they go around
+    /// the point, counterclockwise positive in a y-up system, whatever the
+    /// fill rule. Open subpaths are treated as closed, as in `contains`,
+    /// and points exactly on the outline may be counted either way.
+    pub fn winding(&self, x: f64, y: f64) -> i32 {
+        let path = lock(&self.path);
+        let point = Point::new(x, y);
+        if has_open_subpaths(&path) {
+            close_subpaths(&path).winding(point)
+        } else {
+            path.winding(point)
+        }
+    }
+
+    /// Check whether every segment is a horizontal or vertical line
+    ///
+    /// A line counts as horizontal or vertical if its ends differ by at
+    /// most `tolerance` in y or in x. Closing edges are checked too, and any
+    /// curve makes the path non-rectilinear. An empty path is rectilinear.
+    pub fn is_rectilinear(&self, tolerance: f64) -> bool {
+        let path = lock(&self.path);
+        let rectilinear = path.segments().all(|seg| match seg {
+            PathSeg::Line(line) => {
+                (line.p1.x - line.p0.x).abs() <= tolerance
+                    || (line.p1.y - line.p0.y).abs() <= tolerance
+            }
+            _ => false,
+        });
+        rectilinear
+    }
+
+    /// Get the area moments of inertia of the filled path about its centroid
+    ///
+    /// Curves are flattened first. Subpaths nested inside an odd number of
+    /// others are holes and subtract from the moments, whichever way they
+    /// run. Returns `None` if the path encloses no area.
+    pub fn moment_of_inertia(&self) -> Option<MomentOfInertia> {
+        let (_, sums) = filled_moments(&lock(&self.path));
+        let [area, sx, sy, ixx, iyy, ixy] = sums;
+        if area <= 0.0 || !area.is_finite() {
+            return None;
+        }
+        let (cx, cy) = (sx / area, sy / area);
+        Some(MomentOfInertia {
+            ixx: ixx - area * cy * cy,
+            iyy: iyy - area * cx * cx,
+            ixy: ixy - area * cx * cy,
+        })
+    }
+
+    /// Get the centroid of the filled path
+    ///
+    /// This is the center of mass of the filled area, with holes subtracted
+    /// as in `moment_of_inertia`, and is the natural pivot for rotating or
+    /// scaling the shape. Curves are flattened first. Returns `None` if the
+    /// path encloses no area.
+    pub fn centroid(&self) -> Option<Point2D> {
+        let (origin, sums) = filled_moments(&lock(&self.path));
+        let [area, sx, sy, ..] = sums;
+        if area <= 0.0 || !area.is_finite() {
+            return None;
+        }
+        Some(Point2D::from(origin + kurbo::Vec2::new(sx / area, sy / area)))
+    }
+
+    /// Get a copy of the path with curves approximated by lines
+    ///
+    /// The lines stay within `tolerance` of the original curves, so smaller
+    /// tolerances give more of them. The result has only `MoveTo`, `LineTo`
+    /// and `ClosePath` elements. This is `flatten_with` using
+    /// `FlattenMethod::AdaptiveTolerance`.
+    pub fn flatten(&self, tolerance: f64) -> Arc<BezierPath> {
+        self.flatten_with(FlattenMethod::AdaptiveTolerance { tolerance })
+    }
+
+    /// Get a copy of the path moved so that its center is at the origin
+    ///
+    /// With `use_centroid`, the center is the centroid of the filled area,
+    /// with holes subtracted; a path that encloses no area falls back to
+    /// the bounding box center, which is used otherwise. Empty paths are
+    /// returned unchanged.
+    pub fn center_on_origin(&self, use_centroid: bool) -> Arc<BezierPath> {
+        let mut path = self.to_kurbo_path();
+        if path.elements().is_empty() {
+            return Arc::new(BezierPath::from_kurbo_path(path));
+        }
+        let (origin, sums) = filled_moments(&path);
+        let [area, sx, sy, ..] = sums;
+        let center = if use_centroid && area > 0.0 && area.is_finite() {
+            origin + kurbo::Vec2::new(sx / area, sy / area)
+        } else {
+            origin
+        };
+        path.apply_affine(Affine::translate(-center.to_vec2()));
+        Arc::new(BezierPath::from_kurbo_path(path))
+    }
+
+    /// Flatten the path like `flatten`, but with at most `max_vertices` vertices
+    ///
+    /// If flattening to within `tolerance` would give more vertices than
+    /// that, the tolerance is doubled until it doesn't and `truncated` is
+    /// set; the result is still a valid path with the same subpaths, just
+    /// coarser. If even one line per curve is too many, that is returned.
+    pub fn flatten_capped(&self, tolerance: f64, max_vertices: u64) -> CappedFlattening {
+        let path = self.to_kurbo_path();
+        let vertex_count = |path: &BezPath| {
+            path.elements().iter().filter(|el| el.end_point().is_some()).count() as u64
+        };
+        let flatten = |tolerance: f64| {
+            let mut flattened = BezPath::new();
+            kurbo::flatten(path.iter(), tolerance, |el| flattened.push(el));
+            flattened
+        };
+
+        let mut flattened = if tolerance > 0.0 {
+            flatten(tolerance)
+        } else {
+            split_curves(&path, |_| 1, None)
+        };
+        if vertex_count(&flattened) <= max_vertices {
+            return CappedFlattening {
+                path: Arc::new(BezierPath::from_kurbo_path(flattened)),
+                truncated: false,
+            };
+        }
+
+        let coarsest = split_curves(&path, |_| 1, None);
+        if vertex_count(&coarsest) >= max_vertices {
+            flattened = coarsest;
+        } else {
+            let mut tolerance = tolerance;
+            while vertex_count(&flattened) > max_vertices && tolerance.is_finite() {
+                tolerance *= 2.0;
+                flattened = flatten(tolerance);
+            }
+        }
+        CappedFlattening {
+            path: Arc::new(BezierPath::from_kurbo_path(flattened)),
+            truncated: true,
+        }
+    }
+
+    /// Get the control polygon of the path, for drawing handles
+    ///
+    /// The result is an open polyline through every on-curve and off-curve
+    /// point in order, one subpath per subpath of this path; a cubic adds
+    /// lines to its two control points and then its end point. Closed
+    /// subpaths end with a line back to their start, but are left open.
+    pub fn control_polygon(&self) -> Arc<BezierPath> {
+        let mut polygon = BezPath::new();
+        let mut start = None;
+        for el in lock(&self.path).iter() {
+            match el {
+                PathEl::MoveTo(p) => {
+                    polygon.move_to(p);
+                    start = Some(p);
+                }
+                PathEl::LineTo(p) => polygon.line_to(p),
+                PathEl::QuadTo(p1, p2) => {
+                    polygon.line_to(p1);
+                    polygon.line_to(p2);
+                }
+                PathEl::CurveTo(p1, p2, p3) => {
+                    polygon.line_to(p1);
+                    polygon.line_to(p2);
+                    polygon.line_to(p3);
+                }
+                PathEl::ClosePath => {
+                    if let Some(start) = start {
+                        polygon.line_to(start);
+                    }
+                }
+            }
+        }
+        Arc::new(BezierPath::from_kurbo_path(polygon))
+    }
+
+    /// Get a copy of the path with every subpath traversed backwards
+    ///
+    /// Each subpath starts at its old end point and control points are
+    /// swapped, so the geometry is unchanged but the orientation flips.
+    /// Closed subpaths stay closed.
+    pub fn reversed(&self) -> Arc<BezierPath> {
+        let reversed = lock(&self.path).reverse_subpaths();
+        Arc::new(BezierPath::from_kurbo_path(reversed))
+    }
+
+    /// Check the path against the preconditions of a physics collider
+    ///
+    /// A collider needs closed, finite subpaths that enclose some area
+    /// without crossing, and at most `max_vertices` vertices. Each check is
+    /// reported separately so a rejected path can be given a reason.
+    pub fn physics_ready(&self, max_vertices: u64) -> PhysicsReadiness {
+        let path = self.to_kurbo_path();
+        let subpaths = split_subpaths(&path);
+        let is_closed = !subpaths.is_empty()
+            && subpaths
+                .iter()
+                .all(|subpath| matches!(subpath.elements().last(), Some(PathEl::ClosePath)));
+        let is_finite = check_finite(&path).is_ok();
+        let vertex_count = path.elements().iter().filter(|el| el.end_point().is_some()).count();
+
+        let (is_simple, has_area) = if is_finite {
+            let bbox = path.bounding_box();
+            let diagonal = bbox.width().hypot(bbox.height());
+            (!self_intersects(&path), path.area().abs() > diagonal * diagonal * 1e-9)
+        } else {
+            (false, false)
+        };
+
+        let within_vertex_limit = vertex_count as u64 <= max_vertices;
+        PhysicsReadiness {
+            is_closed,
+            is_simple,
+            has_area,
+            is_finite,
+            vertex_count: vertex_count as u64,
+            within_vertex_limit,
+            ready: is_closed && is_simple && has_area && is_finite && within_vertex_limit,
+        }
+    }
+
+    /// Triangulate the region the path fills, as a flat f32 vertex buffer
+    ///
+    /// Curves are flattened to within `tolerance` and open subpaths are
+    /// treated as closed. The result holds the x and y of each vertex of
+    /// each counterclockwise triangle in turn, with no index buffer, ready to
+    /// upload and draw as a triangle list. Paths with non-finite coordinates
+    /// give an empty buffer.
+    pub fn to_triangle_vertices_f32(&self, fill_rule: FillRule, tolerance: f64) -> Vec<f32> {
+        let path = self.to_kurbo_path();
+        if check_finite(&path).is_err() {
+            return Vec::new();
+        }
+        let rings: Vec<Vec<Point>> = flatten_subpaths(&path, tolerance)
+            .into_iter()
+            .map(|polyline| polyline.points)
+            .collect();
+        fill_triangles(&rings, fill_rule)
+            .iter()
+            .flatten()
+            .flat_map(|p| [p.x as f32, p.y as f32])
+            .collect()
+    }
+}
+
+impl BezierPath {
+    /// Get a clone of the internal kurbo path
+    fn to_kurbo_path(&self) -> BezPath {
+        lock(&self.path).clone()
+    }
+
+    /// Replace the contents of `buffer` with this path, reusing its allocation
+    ///
+    /// Open subpaths are closed on the way, as by `close_subpaths`.
+    fn copy_closed_into(&self, buffer: &mut BezPath) {
+        let close_last = |buffer: &mut BezPath| {
+            if buffer.elements().last().is_some_and(|el| *el != PathEl::ClosePath) {
+                buffer.close_path();
+            }
+        };
+        buffer.truncate(0);
+        for el in lock(&self.path).iter() {
+            if matches!(el, PathEl::MoveTo(_)) {
+                close_last(buffer);
+            }
+            buffer.push(el);
+        }
+        close_last(buffer);
+    }
+}
+
+/// Lock a mutex, recovering the data if another thread panicked while holding it.
+///
+/// Every update to our paths leaves them valid, so a poisoned lock's data is
+/// still safe to use, and refusing it would fail every later call instead.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn convert_contours_to_paths(contours: linesweeper::topology::Contours) -> Vec<BezierPath> {
+    contours
+        .contours()
+        // Collinear edges that overlap can leave the contour doubling back
+        // along itself, so fold those spans into single edges
+        .map(|contour| remove_backtracks(&contour.path))
+        // Coincident edges running in opposite directions cancel out, but
+        // the sweep can still trace a zero-area contour along them
+        .filter(|path| !is_sliver(path))
+        .map(BezierPath::from_kurbo_path)
+        .collect()
+}
+
+/// Merge the spans where a contour's lines double back along themselves.
+///
+/// Where a line is followed by one running back along it, the two are
+/// replaced by a single line from the start of the first to the end of the
+/// second, dropped entirely if that is where the first began. Subpaths with
+/// nothing to merge, and all curves, are left unchanged.
+fn remove_backtracks(path: &BezPath) -> BezPath {
+    let mut result = BezPath::new();
+    for subpath in split_subpaths(path) {
+        let elements = subpath.elements();
+        let (Some(PathEl::MoveTo(start)), Some(PathEl::ClosePath)) =
+            (elements.first(), elements.last())
+        else {
+            result.extend(subpath.iter());
+            continue;
+        };
+        // The drawn elements, cyclically, with the closing edge made explicit
+        let mut ring: Vec<PathEl> = elements[1..elements.len() - 1].to_vec();
+        if ring.last().and_then(|el| el.end_point()) != Some(*start) {
+            ring.push(PathEl::LineTo(*start));
+        }
+
+        let mut merged = false;
+        let mut i = 0;
+        while ring.len() > 2 && i < ring.len() {
+            let next = (i + 1) % ring.len();
+            let p = ring[(i + ring.len() - 1) % ring.len()].end_point().unwrap_or_default();
+            let (PathEl::LineTo(q), PathEl::LineTo(r)) = (ring[i], ring[next]) else {
+                i += 1;
+                continue;
+            };
+            let (d1, d2) = (q - p, r - q);
+            let collinear = d1.cross(d2).abs() <= d1.hypot() * d2.hypot() * 1e-9;
+            if !collinear || d1.dot(d2) >= 0.0 {
+                i += 1;
+                continue;
+            }
+            merged = true;
+            if r.distance(p) <= (d1.hypot() + d2.hypot()) * 1e-9 {
+                ring.remove(i.max(next));
+                ring.remove(i.min(next));
+            } else {
+                // The next line now starts where this one did
+                ring.remove(i);
+            }
+            // The merged line may now double back along its predecessor
+            i = i.saturating_sub(1).min(ring.len().saturating_sub(1));
+        }
+
+        if !merged {
+            result.extend(subpath.iter());
+        } else if let Some(end) = ring.last().and_then(|el| el.end_point()) {
+            result.move_to(end);
+            result.extend(ring);
+            result.close_path();
+        }
+    }
+    result
+}
+
+/// A copy of a path without zero-length segments or redundant line vertices.
+///
+/// See `BezierPath::cleanup`. Subpaths that don't start with a `MoveTo` are
+/// left unchanged.
+fn cleanup_path(path: &BezPath, tolerance: f64) -> BezPath {
+    let mut result = BezPath::new();
+    for subpath in split_subpaths(path) {
+        let elements = subpath.elements();
+        let Some(&PathEl::MoveTo(start)) = elements.first() else {
+            result.extend(subpath.iter());
+            continue;
+        };
+        let closed = elements.last() == Some(&PathEl::ClosePath);
+        // The drawn elements, with the closing edge of a closed subpath made explicit
+        let mut drawn: Vec<PathEl> = elements[1..].to_vec();
+        if closed {
+            drawn.pop();
+            if drawn.last().and_then(|el| el.end_point()) != Some(start) {
+                drawn.push(PathEl::LineTo(start));
+            }
+        }
+
+        let start_of = |drawn: &[PathEl], i: usize| {
+            let previous = match i {
+                0 if closed => drawn.last(),
+                0 => None,
+                _ => drawn.get(i - 1),
+            };
+            previous.and_then(|el| el.end_point()).unwrap_or(start)
+        };
+        let mut i = 0;
+        while i < drawn.len() {
+            let p = start_of(&drawn, i);
+            let points = match drawn[i] {
+                PathEl::LineTo(q) => vec![q],
+                PathEl::QuadTo(q1, q2) => vec![q1, q2],
+                PathEl::CurveTo(q1, q2, q3) => vec![q1, q2, q3],
+                PathEl::MoveTo(_) | PathEl::ClosePath => vec![],
+            };
+            let degenerate = points.iter().all(|q| q.distance(p) <= tolerance);
+            let next = if closed && drawn.len() > 2 {
+                Some(drawn[(i + 1) % drawn.len()])
+            } else {
+                drawn.get(i + 1).copied()
+            };
+            let redundant = match (drawn[i], next) {
+                (PathEl::LineTo(q), Some(PathEl::LineTo(r))) => {
+                    kurbo::Line::new(p, r).nearest(q, 0.0).distance_sq <= tolerance * tolerance
+                }
+                _ => false,
+            };
+            if degenerate || redundant {
+                drawn.remove(i);
+                // The element before may have become redundant in turn
+                i = i.saturating_sub(1);
+            } else {
+                i += 1;
+            }
+        }
+
+        if closed {
+            let Some(first) = drawn.last().and_then(|el| el.end_point()) else {
+                continue;
+            };
+            if drawn.len() > 1 && matches!(drawn.last(), Some(PathEl::LineTo(_))) {
+                drawn.pop();
+            }
+            result.move_to(first);
+            result.extend(drawn);
+            result.close_path();
+        } else if !drawn.is_empty() {
+            result.move_to(start);
+            result.extend(drawn);
+        }
+    }
+    result
+}
+
+/// Whether a contour encloses no area relative to its size.
+fn is_sliver(contour: &BezPath) -> bool {
+    let bbox = contour.bounding_box();
+    let size = bbox.width().hypot(bbox.height());
+    contour.area().abs() <= size * size * 1e-9
+}
+
+/// A flattened subpath: a polyline, plus whether it was explicitly closed.
+struct Polyline {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+fn flatten_subpaths(path: &BezPath, tolerance: f64) -> Vec<Polyline> {
+    let mut polylines: Vec<Polyline> = Vec::new();
+    kurbo::flatten(path.iter(), tolerance, |el| match el {
+        PathEl::MoveTo(p) => polylines.push(Polyline {
+            points: vec![p],
+            closed: false,
+        }),
+        PathEl::LineTo(p) => {
+            if let Some(polyline) = polylines.last_mut() {
+                polyline.points.push(p);
+            }
+        }
+        PathEl::ClosePath => {
+            if let Some(polyline) = polylines.last_mut() {
+                polyline.closed = true;
+                // The closing edge is implicit, so drop a repeated start point
+                if polyline.points.len() > 1 && polyline.points.first() == polyline.points.last() {
+                    polyline.points.pop();
+                }
+            }
+        }
+        _ => {}
+    });
+    polylines
+}
+
+/// A copy of a path with every quadratic raised to the equivalent cubic.
+fn raise_quads(path: &BezPath) -> BezPath {
+    let (mut start, mut current) = (Point::ZERO, Point::ZERO);
+    let mut raised = BezPath::new();
+    for &el in path.elements() {
+        match el {
+            PathEl::QuadTo(p1, p2) => {
+                let cubic = kurbo::QuadBez::new(current, p1, p2).raise();
+                raised.push(PathEl::CurveTo(cubic.p1, cubic.p2, cubic.p3));
+            }
+            el => raised.push(el),
+        }
+        match el {
+            PathEl::MoveTo(p) => (start, current) = (p, p),
+            PathEl::ClosePath => current = start,
+            el => current = el.end_point().unwrap_or(current),
+        }
+    }
+    raised
+}
+
+/// Area, first moments and second moments of the region a path fills.
+///
+/// Curves are flattened, and subpaths nested inside an odd number of others
+/// are holes whichever way they run. The moments are taken about the
+/// returned origin, the middle of the path, to limit cancellation.
+fn filled_moments(path: &BezPath) -> (Point, [f64; 6]) {
+    let bbox = path.bounding_box();
+    let tolerance = bbox.width().hypot(bbox.height()) * 1e-6;
+    let origin = bbox.center();
+    let rings: Vec<Vec<Point>> = flatten_subpaths(path, tolerance)
+        .into_iter()
+        .map(|polyline| polyline.points.iter().map(|p| (*p - origin).to_point()).collect())
+        .filter(|ring: &Vec<Point>| ring.len() >= 3)
+        .collect();
+    let parents = ring_parents(&rings);
+
+    let mut sums = [0.0; 6];
+    for (i, ring) in rings.iter().enumerate() {
+        let ring_sums = ring_moments(ring);
+        let orientation = ring_sums[0].signum();
+        let sign = if nesting_depth(&parents, i).is_multiple_of(2) {
+            orientation
+        } else {
+            -orientation
+        };
+        for (sum, value) in sums.iter_mut().zip(ring_sums) {
+            *sum += sign * value;
+        }
+    }
+    (origin, sums)
+}
+
+/// Shoelace area of a ring, positive when counterclockwise (y up).
+fn ring_signed_area(ring: &[Point]) -> f64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| ring[i].to_vec2().cross(ring[(i + 1) % n].to_vec2()))
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Winding number of a ring (implicitly closed) around a point.
+fn ring_winding(ring: &[Point], p: Point) -> i32 {
+    let n = ring.len();
+    let mut winding = 0;
+    for i in 0..n {
+        let (p0, p1) = (ring[i], ring[(i + 1) % n]);
+        let side = (p1 - p0).cross(p - p0);
+        if p0.y <= p.y {
+            if p1.y > p.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if p1.y <= p.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// For each ring, the index of the smallest ring that encloses it, if any.
+fn ring_parents(rings: &[Vec<Point>]) -> Vec<Option<usize>> {
+    let areas: Vec<f64> = rings.iter().map(|r| ring_signed_area(r).abs()).collect();
+    rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            let probe = ring[0];
+            (0..rings.len())
+                .filter(|&j| j != i && areas[j] > areas[i] && ring_winding(&rings[j], probe) != 0)
+                .min_by(|&a, &b| areas[a].total_cmp(&areas[b]))
+        })
+        .collect()
+}
+
+/// Like `ring_parents`, but for curved closed subpaths.
+fn subpath_parents(subpaths: &[BezPath]) -> Vec<Option<usize>> {
+    let areas: Vec<f64> = subpaths.iter().map(|p| p.area().abs()).collect();
+    subpaths
+        .iter()
+        .enumerate()
+        .map(|(i, subpath)| {
+            // Probe mid-segment, as contours may touch each other at vertices
+            let probe = subpath.segments().next()?.eval(0.5);
+            (0..subpaths.len())
+                .filter(|&j| j != i && areas[j] > areas[i] && subpaths[j].winding(probe) != 0)
+                .min_by(|&a, &b| areas[a].total_cmp(&areas[b]))
+        })
+        .collect()
+}
+
+fn nesting_depth(parents: &[Option<usize>], mut index: usize) -> usize {
+    let mut depth = 0;
+    while let Some(parent) = parents[index] {
+        depth += 1;
+        index = parent;
+    }
+    depth
+}
+
+fn geojson_ring(ring: &[Point], counterclockwise: bool) -> String {
+    let mut points: Vec<Point> = ring.to_vec();
+    if (ring_signed_area(&points) > 0.0) != counterclockwise {
+        points.reverse();
+    }
+    // GeoJSON rings repeat their first position at the end
+    points.push(points[0]);
+    let positions: Vec<String> = points
+        .iter()
+        .map(|p| format!("[{},{}]", p.x, p.y))
+        .collect();
+    format!("[{}]", positions.join(","))
+}
+
+fn polylines_to_path(polylines: &[Polyline]) -> BezPath {
+    let mut path = BezPath::new();
+    for polyline in polylines {
+        let Some((first, rest)) = polyline.points.split_first() else {
+            continue;
+        };
+        path.move_to(*first);
+        for p in rest {
+            path.line_to(*p);
+        }
+        if polyline.closed {
+            path.close_path();
+        }
+    }
+    path
+}
+
+/// Distance from `p` to the segment from `a` to `b`.
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let ab = b - a;
+    let len2 = ab.hypot2();
+    if len2 == 0.0 {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / len2).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+/// Ramer-Douglas-Peucker simplification of an open polyline.
+fn rdp(points: &[Point], epsilon: f64) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (index, distance) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i + 1, distance_to_segment(*p, first, last)))
+        .fold((0, -1.0), |best, cur| if cur.1 > best.1 { cur } else { best });
+
+    if distance <= epsilon {
+        return vec![first, last];
+    }
+    let mut result = rdp(&points[..=index], epsilon);
+    result.pop();
+    result.extend(rdp(&points[index..], epsilon));
+    result
+}
+
+/// RDP-simplify a polyline, never collapsing a subpath entirely.
+///
+/// Closed polylines are split at the vertex farthest from their start and
+/// keep at least three vertices, so they still enclose an area.
+fn simplify_polyline(polyline: &Polyline, epsilon: f64) -> Polyline {
+    let points = &polyline.points;
+    if !polyline.closed || points.len() <= 3 {
+        return Polyline {
+            points: rdp(points, epsilon),
+            closed: polyline.closed,
+        };
+    }
+
+    let start = points[0];
+    let far = (1..points.len())
+        .max_by(|&a, &b| points[a].distance(start).total_cmp(&points[b].distance(start)))
+        .unwrap_or(1);
+    let mut simplified = rdp(&points[..=far], epsilon);
+    simplified.pop();
+    let mut tail: Vec<Point> = points[far..].to_vec();
+    tail.push(start);
+    simplified.extend(rdp(&tail, epsilon));
+    simplified.pop();
+
+    if simplified.len() < 3 {
+        let (a, b) = (simplified[0], simplified[simplified.len() - 1]);
+        let extra = points
+            .iter()
+            .copied()
+            .max_by(|p, q| distance_to_segment(*p, a, b).total_cmp(&distance_to_segment(*q, a, b)))
+            .unwrap_or(start);
+        simplified.insert(1, extra);
+    }
+    Polyline {
+        points: simplified,
+        closed: true,
+    }
+}
+
+/// Split a path into its subpaths, one `BezPath` per `MoveTo`.
+fn split_subpaths(path: &BezPath) -> Vec<BezPath> {
+    let mut subpaths: Vec<BezPath> = Vec::new();
+    for el in path.elements() {
+        if matches!(el, PathEl::MoveTo(_)) || subpaths.is_empty() {
+            subpaths.push(BezPath::new());
+        }
+        if let Some(subpath) = subpaths.last_mut() {
+            subpath.push(*el);
+        }
+    }
+    subpaths
+}
+
+/// Replace the end point of a drawing element, keeping its control points.
+fn with_end_point(el: PathEl, p: Point) -> PathEl {
+    match el {
+        PathEl::MoveTo(_) => PathEl::MoveTo(p),
+        PathEl::LineTo(_) => PathEl::LineTo(p),
+        PathEl::QuadTo(c, _) => PathEl::QuadTo(c, p),
+        PathEl::CurveTo(c1, c2, _) => PathEl::CurveTo(c1, c2, p),
+        PathEl::ClosePath => PathEl::ClosePath,
+    }
+}
+
+/// Real roots of `a·t² + b·t + c` in [0, 1].
+fn unit_quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let roots = if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            vec![]
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let disc = b * b - 4.0 * a * c;
+        if disc < 0.0 {
+            vec![]
+        } else {
+            let sq = disc.sqrt();
+            vec![(-b - sq) / (2.0 * a), (-b + sq) / (2.0 * a)]
+        }
+    };
+    roots.into_iter().filter(|t| (0.0..=1.0).contains(t)).collect()
+}
+
+/// Parameters where the derivative of a cubic vanishes.
+fn cubic_cusps(cubic: &CubicBez) -> Vec<f64> {
+    // The derivative is a quadratic Bézier; find where both its coordinates vanish
+    let deriv = cubic.deriv();
+    let (p0, p1, p2) = (deriv.p0.to_vec2(), deriv.p1.to_vec2(), deriv.p2.to_vec2());
+    let a = p0 - p1 * 2.0 + p2;
+    let b = (p1 - p0) * 2.0;
+    let scale = p0.hypot().max(p1.hypot()).max(p2.hypot());
+
+    let mut cusps: Vec<f64> = unit_quadratic_roots(a.x, b.x, p0.x)
+        .into_iter()
+        .chain(unit_quadratic_roots(a.y, b.y, p0.y))
+        .filter(|&t| deriv.eval(t).to_vec2().hypot() <= 1e-9 * scale)
+        .collect();
+    cusps.sort_by(f64::total_cmp);
+    cusps.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+    cusps
+}
+
+/// Whether a cubic crosses itself at two distinct parameters in [0, 1].
+fn cubic_has_loop(cubic: &CubicBez) -> bool {
+    // With B(t) = a·t³ + b·t² + c·t + p0, dividing B(s) - B(t) = 0 by s - t
+    // leaves a·(σ² - π) + b·σ + c = 0 for σ = s + t and π = s·t, which is
+    // linear in σ² - π and σ
+    let [p0, p1, p2, p3] = [cubic.p0, cubic.p1, cubic.p2, cubic.p3].map(Point::to_vec2);
+    let a = p3 - p0 + (p1 - p2) * 3.0;
+    let b = (p0 - p1 * 2.0 + p2) * 3.0;
+    let c = (p1 - p0) * 3.0;
+    let det = a.cross(b);
+    if det.abs() <= 1e-12 * a.hypot() * b.hypot() {
+        return false;
+    }
+    let u = b.cross(c) / det;
+    let sigma = c.cross(a) / det;
+    // s and t are the roots of z² - σ·z + π, whose discriminant is σ² - 4π
+    let discriminant = 4.0 * u - 3.0 * sigma * sigma;
+    if discriminant <= 0.0 {
+        return false;
+    }
+    let root = discriminant.sqrt();
+    let (s, t) = ((sigma - root) / 2.0, (sigma + root) / 2.0);
+    s >= 0.0 && t <= 1.0
+}
+
+/// Turn closed subpaths into open ones, making the closing edge explicit.
+fn open_subpaths(path: &BezPath) -> BezPath {
+    let mut result = BezPath::new();
+    let mut start = None;
+    let mut last = None;
+    for el in path.elements() {
+        match el {
+            PathEl::MoveTo(p) => {
+                start = Some(*p);
+                result.push(*el);
+            }
+            PathEl::ClosePath => {
+                if let (Some(start), Some(last)) = (start, last) {
+                    if start != last {
+                        result.line_to(start);
+                    }
+                }
+            }
+            _ => result.push(*el),
+        }
+        last = el.end_point().or(start);
+    }
+    result
+}
+
+/// The on- and off-curve points that define a segment, in order.
+fn seg_points(seg: &PathSeg) -> Vec<Point> {
+    match seg {
+        PathSeg::Line(line) => vec![line.p0, line.p1],
+        PathSeg::Quad(quad) => vec![quad.p0, quad.p1, quad.p2],
+        PathSeg::Cubic(cubic) => vec![cubic.p0, cubic.p1, cubic.p2, cubic.p3],
+    }
+}
+
+fn segs_approx_eq(a: &PathSeg, b: &PathSeg, tolerance: f64) -> bool {
+    let (points_a, points_b) = (seg_points(a), seg_points(b));
+    points_a.len() == points_b.len()
+        && points_a
+            .iter()
+            .zip(&points_b)
+            .all(|(p, q)| p.distance(*q) <= tolerance)
+}
+
+/// Whether two subpaths trace the same geometry, regardless of direction
+/// and, for closed subpaths, of which vertex they start at.
+fn subpaths_match(a: &BezPath, b: &BezPath, tolerance: f64) -> bool {
+    let is_closed = |p: &BezPath| p.elements().last() == Some(&PathEl::ClosePath);
+    if is_closed(a) != is_closed(b) {
+        return false;
+    }
+
+    let segs_a: Vec<PathSeg> = a.segments().collect();
+    let segs_b: Vec<PathSeg> = b.segments().collect();
+    if segs_a.len() != segs_b.len() {
+        return false;
+    }
+    let n = segs_a.len();
+    if n == 0 {
+        let start = |p: &BezPath| p.elements().first().and_then(|el| el.end_point());
+        return match (start(a), start(b)) {
+            (Some(p), Some(q)) => p.distance(q) <= tolerance,
+            _ => false,
+        };
+    }
+
+    let reversed_b: Vec<PathSeg> = segs_b.iter().rev().map(|seg| seg.reverse()).collect();
+    let offsets = if is_closed(a) { n } else { 1 };
+    [&segs_b, &reversed_b].iter().any(|candidate| {
+        (0..offsets).any(|offset| {
+            (0..n).all(|i| segs_approx_eq(&segs_a[i], &candidate[(i + offset) % n], tolerance))
+        })
+    })
+}
+
+/// Indices of subpaths that duplicate an earlier subpath.
+fn duplicate_subpaths(subpaths: &[BezPath], tolerance: f64) -> Vec<usize> {
+    (0..subpaths.len())
+        .filter(|&i| (0..i).any(|j| subpaths_match(&subpaths[j], &subpaths[i], tolerance)))
+        .collect()
+}
+
+/// Apply `f` to every point of an element, control points included.
+fn map_points(el: PathEl, f: impl Fn(Point) -> Point) -> PathEl {
+    match el {
+        PathEl::MoveTo(p) => PathEl::MoveTo(f(p)),
+        PathEl::LineTo(p) => PathEl::LineTo(f(p)),
+        PathEl::QuadTo(p1, p2) => PathEl::QuadTo(f(p1), f(p2)),
+        PathEl::CurveTo(p1, p2, p3) => PathEl::CurveTo(f(p1), f(p2), f(p3)),
+        PathEl::ClosePath => PathEl::ClosePath,
+    }
+}
+
+/// Apply `f` to the end point of an element, leaving its control points alone.
+fn map_end_point(el: PathEl, f: impl Fn(Point) -> Point) -> PathEl {
+    match el {
+        PathEl::MoveTo(p) => PathEl::MoveTo(f(p)),
+        PathEl::LineTo(p) => PathEl::LineTo(f(p)),
+        PathEl::QuadTo(p1, p2) => PathEl::QuadTo(p1, f(p2)),
+        PathEl::CurveTo(p1, p2, p3) => PathEl::CurveTo(p1, p2, f(p3)),
+        PathEl::ClosePath => PathEl::ClosePath,
+    }
+}
+
+fn snap_to_grid(path: &BezPath, grid: f64) -> BezPath {
+    let snap = |p: Point| Point::new((p.x / grid).round() * grid, (p.y / grid).round() * grid);
+    path.elements().iter().map(|el| map_points(*el, snap)).collect()
+}
+
+/// Build a path from segments, checking that it starts with a `MoveTo`.
+fn path_from_segments(
+    segments: impl IntoIterator<Item = PathSegment>,
+) -> Result<BezPath, LineSweeperError> {
+    starting_with_move(segments.into_iter().map(PathEl::from).collect())
+}
+
+/// Check that a path is empty or starts with a `MoveTo`.
+fn starting_with_move(path: BezPath) -> Result<BezPath, LineSweeperError> {
+    match path.elements().first() {
+        None | Some(PathEl::MoveTo(_)) => Ok(path),
+        Some(_) => Err(LineSweeperError::InvalidArgument(
+            "path must start with a MoveTo segment".to_string(),
+        )),
+    }
+}
+
+/// Whether all turns of a ring (implicitly closed) go the same way.
+fn ring_is_convex(ring: &[Point], tolerance: f64) -> bool {
+    let n = ring.len();
+    if n < 3 {
+        return false;
+    }
+    let mut sign = 0.0;
+    let mut total_turn = 0.0;
+    for i in 0..n {
+        let (p0, p1, p2) = (ring[i], ring[(i + 1) % n], ring[(i + 2) % n]);
+        let (e1, e2) = (p1 - p0, p2 - p1);
+        let cross = e1.cross(e2);
+        // Distance of the middle vertex from the chord through its neighbours
+        let chord = (p2 - p0).hypot();
+        if chord == 0.0 || cross.abs() / chord <= tolerance {
+            continue;
+        }
+        if sign == 0.0 {
+            sign = cross.signum();
+        } else if cross.signum() != sign {
+            return false;
+        }
+        total_turn += cross.atan2(e1.dot(e2));
+    }
+    // Turning more than once around (e.g. a pentagram) isn't convex
+    sign != 0.0 && total_turn.abs() < 3.0 * std::f64::consts::PI
+}
+
+/// Unit direction of a segment at its start, skipping coincident control points.
+fn start_direction(seg: &PathSeg) -> Option<kurbo::Vec2> {
+    let points = seg_points(seg);
+    points[1..]
+        .iter()
+        .map(|p| *p - points[0])
+        .find(|v| v.hypot() > 0.0)
+        .map(|v| v.normalize())
+}
+
+/// The point at arc length `s` along segments given with their lengths.
+///
+/// Distances past the end give the end point.
+fn point_at_arclen(segments: &[(PathSeg, f64)], mut s: f64, accuracy: f64) -> Point {
+    for (seg, length) in segments {
+        if s <= *length {
+            return seg.eval(seg.inv_arclen(s, accuracy));
+        }
+        s -= length;
+    }
+    segments.last().map_or(Point::ZERO, |(seg, _)| seg.end())
+}
+
+/// Arc length accuracy for `point_at` and `tangent_at`, which take none.
+const ARCLEN_ACCURACY: f64 = 1e-9;
+
+/// The segment and parameter a fraction `t` of the way along a path by arc length.
+///
+/// `t` is clamped to `[0, 1]`, and zero-length segments are never picked
+/// unless the whole path has zero length.
+fn locate_fraction(path: &BezPath, t: f64) -> Option<(PathSeg, f64)> {
+    let segments: Vec<(PathSeg, f64)> = path
+        .segments()
+        .map(|seg| (seg, seg.arclen(ARCLEN_ACCURACY)))
+        .collect();
+    let total: f64 = segments.iter().map(|(_, length)| length).sum();
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+    let mut s = total * t;
+    for (seg, length) in &segments {
+        if *length > 0.0 && s <= *length {
+            return Some((*seg, seg.inv_arclen(s, ARCLEN_ACCURACY)));
+        }
+        s -= length;
+    }
+    let (seg, _) = segments
+        .iter()
+        .rev()
+        .find(|(_, length)| *length > 0.0)
+        .or(segments.last())?;
+    Some((*seg, 1.0))
+}
+
+/// Area enclosed by non-overlapping contours, with nested contours as holes.
+///
+/// Orientation is ignored: contours at even nesting depth add their area
+/// and contours at odd depth subtract it.
+fn filled_area(contours: &[BezPath]) -> f64 {
+    let areas: Vec<f64> = contours.iter().map(|contour| contour.area().abs()).collect();
+    nested_area(&areas, &subpath_parents(contours))
+}
+
+/// Sum of unsigned areas, subtracting those at odd nesting depth.
+fn nested_area(areas: &[f64], parents: &[Option<usize>]) -> f64 {
+    areas
+        .iter()
+        .enumerate()
+        .map(|(i, area)| {
+            if nesting_depth(parents, i).is_multiple_of(2) {
+                *area
+            } else {
+                -area
+            }
+        })
+        .sum()
+}
+
+/// Largest isoperimetric quotient (4π·area / perimeter²) of a ribbon-like shape.
+const RIBBON_MAX_ROUNDNESS: f64 = 0.5;
+
+fn ring_perimeter(ring: &[Point]) -> f64 {
+    let n = ring.len();
+    (0..n).map(|i| ring[i].distance(ring[(i + 1) % n])).sum()
+}
+
+/// Distance from `origin` along `direction` to the first edge of `rings` it hits.
+///
+/// `skip` is the (ring, edge) index of the edge the ray starts on.
+fn ray_distance(
+    rings: &[Vec<Point>],
+    origin: Point,
+    direction: kurbo::Vec2,
+    skip: (usize, usize),
+) -> Option<f64> {
+    let mut nearest: Option<f64> = None;
+    for (i, ring) in rings.iter().enumerate() {
+        for j in 0..ring.len() {
+            if (i, j) == skip {
+                continue;
+            }
+            let (q0, q1) = (ring[j], ring[(j + 1) % ring.len()]);
+            let edge = q1 - q0;
+            let denom = direction.cross(edge);
+            if denom == 0.0 {
+                continue;
+            }
+            let w = q0 - origin;
+            let t = w.cross(edge) / denom;
+            let s = w.cross(direction) / denom;
+            if t > 0.0 && (0.0..=1.0).contains(&s) && nearest.is_none_or(|d| t < d) {
+                nearest = Some(t);
+            }
+        }
+    }
+    nearest
+}
+
+fn parse_turtle(program: &str, step: f64) -> Result<BezPath, LineSweeperError> {
+    if !step.is_finite() {
+        return Err(LineSweeperError::InvalidArgument(format!(
+            "step must be finite, got {step}"
+        )));
+    }
+    let mut path = BezPath::new();
+    let mut position = Point::ZERO;
+    let mut heading: f64 = 0.0;
+    let mut pen_down = false;
+
+    let mut offset = 0;
+    for token in program.split(|c: char| c.is_whitespace()) {
+        let start = offset;
+        offset += token.len() + 1;
+        let mut chars = token.chars();
+        let Some(command) = chars.next() else {
+            continue;
+        };
+        if !"FBMLR".contains(command.to_ascii_uppercase()) {
+            return Err(LineSweeperError::ParseError(format!(
+                "unknown command '{command}' at position {start}"
+            )));
+        }
+        let value = chars.as_str().parse::<f64>().ok().filter(|value| value.is_finite());
+        let value = value.ok_or_else(|| {
+            LineSweeperError::ParseError(format!(
+                "expected a number after '{command}' at position {start}"
+            ))
+        })?;
+
+        match command.to_ascii_uppercase() {
+            'F' | 'B' => {
+                if !pen_down {
+                    path.move_to(position);
+                    pen_down = true;
+                }
+                let sign = if command.eq_ignore_ascii_case(&'F') { 1.0 } else { -1.0 };
+                position += kurbo::Vec2::from_angle(heading) * (sign * value * step);
+                path.line_to(position);
+            }
+            'M' => {
+                position += kurbo::Vec2::from_angle(heading) * (value * step);
+                pen_down = false;
+            }
+            'L' => heading += value.to_radians(),
+            'R' => heading -= value.to_radians(),
+            _ => unreachable!("commands are checked before their numbers"),
+        }
+    }
+    Ok(path)
+}
+
+/// Whether two segments cross at a point interior to both.
+fn segments_cross(a0: Point, a1: Point, b0: Point, b1: Point) -> bool {
+    let side = |p: Point, q0: Point, q1: Point| (q1 - q0).cross(p - q0);
+    let (d0, d1) = (side(b0, a0, a1), side(b1, a0, a1));
+    let (d2, d3) = (side(a0, b0, b1), side(a1, b0, b1));
+    d0 * d1 < 0.0 && d2 * d3 < 0.0
+}
+
+/// Whether any two edges of the rings (implicitly closed) cross.
+fn rings_cross(rings: &[Vec<Point>]) -> bool {
+    let edges: Vec<(usize, usize, Point, Point)> = rings
+        .iter()
+        .enumerate()
+        .flat_map(|(i, ring)| {
+            (0..ring.len()).map(move |j| (i, j, ring[j], ring[(j + 1) % ring.len()]))
+        })
+        .collect();
+    edges.iter().enumerate().any(|(k, &(i, j, a0, a1))| {
+        edges[k + 1..].iter().any(|&(i2, j2, b0, b1)| {
+            // Neighbouring edges of a ring share a vertex but don't cross
+            let n = rings[i].len();
+            let neighbours = i == i2 && (j2 == (j + 1) % n || j == (j2 + 1) % n);
+            !neighbours && segments_cross(a0, a1, b0, b1)
+        })
+    })
+}
+
+/// Triangles covering the region that rings (implicitly closed) fill under a rule.
+///
+/// The plane is cut into horizontal slabs at every vertex and crossing, so
+/// no two edges cross inside a slab and each filled span between adjacent
+/// edges is a trapezoid, emitted as two counterclockwise triangles.
+fn fill_triangles(rings: &[Vec<Point>], fill_rule: FillRule) -> Vec<[Point; 3]> {
+    // Edges run from their lower to their upper end, with the winding they add
+    let edges: Vec<(Point, Point, i32)> = rings
+        .iter()
+        .flat_map(|ring| {
+            (0..ring.len()).filter_map(move |i| {
+                let (p0, p1) = (ring[i], ring[(i + 1) % ring.len()]);
+                match p0.y.total_cmp(&p1.y) {
+                    std::cmp::Ordering::Less => Some((p0, p1, 1)),
+                    std::cmp::Ordering::Greater => Some((p1, p0, -1)),
+                    std::cmp::Ordering::Equal => None,
+                }
+            })
+        })
+        .collect();
+
+    let mut ys: Vec<f64> = edges.iter().flat_map(|&(lo, hi, _)| [lo.y, hi.y]).collect();
+    for (k, &(a0, a1, _)) in edges.iter().enumerate() {
+        for &(b0, b1, _) in &edges[k + 1..] {
+            if segments_cross(a0, a1, b0, b1) {
+                let t = (b0 - a0).cross(b1 - b0) / (a1 - a0).cross(b1 - b0);
+                ys.push(a0.y + t * (a1.y - a0.y));
+            }
+        }
+    }
+    ys.sort_by(f64::total_cmp);
+    ys.dedup();
+
+    let x_at = |(lo, hi, _): (Point, Point, i32), y: f64| {
+        lo.x + (hi.x - lo.x) * ((y - lo.y) / (hi.y - lo.y)).clamp(0.0, 1.0)
+    };
+    let mut triangles = Vec::new();
+    for slab in ys.windows(2) {
+        let (y0, y1) = (slab[0], slab[1]);
+        let mut active: Vec<(Point, Point, i32)> = edges
+            .iter()
+            .copied()
+            .filter(|&(lo, hi, _)| lo.y <= y0 && hi.y >= y1)
+            .collect();
+        active.sort_by(|&a, &b| {
+            (x_at(a, y0) + x_at(a, y1)).total_cmp(&(x_at(b, y0) + x_at(b, y1)))
+        });
+
+        let mut winding = 0;
+        for pair in active.windows(2) {
+            winding += pair[0].2;
+            if !fill_rule.is_inside(winding) {
+                continue;
+            }
+            let (left, right) = (pair[0], pair[1]);
+            let a0 = Point::new(x_at(left, y0), y0);
+            let a1 = Point::new(x_at(right, y0), y0);
+            let b0 = Point::new(x_at(left, y1), y1);
+            let b1 = Point::new(x_at(right, y1), y1);
+            if a1.x > a0.x {
+                triangles.push([a0, a1, b1]);
+            }
+            if b1.x > b0.x {
+                triangles.push([a0, b1, b0]);
+            }
+        }
+    }
+    triangles
+}
+
+/// Element tags, numbered as in `encode_path`, and the coordinates of their points.
+fn flat_commands(path: &BezPath) -> FlatPath {
+    let mut tags = Vec::with_capacity(path.elements().len());
+    let mut coords = Vec::new();
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                tags.push(0);
+                coords.extend([p.x, p.y]);
+            }
+            PathEl::LineTo(p) => {
+                tags.push(1);
+                coords.extend([p.x, p.y]);
+            }
+            PathEl::QuadTo(p1, p2) => {
+                tags.push(2);
+                coords.extend([p1.x, p1.y, p2.x, p2.y]);
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                tags.push(3);
+                coords.extend([p1.x, p1.y, p2.x, p2.y, p3.x, p3.y]);
+            }
+            PathEl::ClosePath => tags.push(4),
+        }
+    }
+    FlatPath { tags, coords }
+}
+
+fn encode_path(path: &BezPath) -> Vec<u8> {
+    let flat = flat_commands(path);
+    let mut coords = flat.coords.iter();
+    let mut data = Vec::new();
+    for tag in flat.tags {
+        data.push(tag);
+        let count = tag_point_count(tag).unwrap_or(0) * 2;
+        for value in coords.by_ref().take(count) {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    data
+}
+
+/// Number of points that follow an element tag, as written by `encode_path`.
+fn tag_point_count(tag: u8) -> Option<usize> {
+    match tag {
+        0 | 1 => Some(1),
+        2 => Some(2),
+        3 => Some(3),
+        4 => Some(0),
+        _ => None,
+    }
+}
+
+/// The element for a tag and its points, which must number `tag_point_count(tag)`.
+fn element_from_tag(tag: u8, points: &[Point]) -> PathEl {
+    match (tag, points) {
+        (0, &[p]) => PathEl::MoveTo(p),
+        (1, &[p]) => PathEl::LineTo(p),
+        (2, &[p1, p2]) => PathEl::QuadTo(p1, p2),
+        (3, &[p1, p2, p3]) => PathEl::CurveTo(p1, p2, p3),
+        _ => PathEl::ClosePath,
+    }
+}
+
+fn decode_path(data: &[u8]) -> Result<BezPath, LineSweeperError> {
+    let mut path = BezPath::new();
+    let mut rest = data;
+    while let Some((&tag, tail)) = rest.split_first() {
+        let offset = data.len() - rest.len();
+        let Some(point_count) = tag_point_count(tag) else {
+            return Err(LineSweeperError::ParseError(format!(
+                "unknown element tag {tag} at byte {offset}"
+            )));
+        };
+        let Some((coords, tail)) = tail.split_at_checked(point_count * 16) else {
+            return Err(LineSweeperError::ParseError(format!(
+                "truncated element at byte {offset}"
+            )));
+        };
+        let points: Vec<Point> = coords
+            .chunks_exact(16)
+            .map(|chunk| {
+                let (x, y) = chunk.split_at(8);
+                Point::new(
+                    f64::from_le_bytes(x.try_into().unwrap()),
+                    f64::from_le_bytes(y.try_into().unwrap()),
+                )
+            })
+            .collect();
+        path.push(element_from_tag(tag, &points));
+        rest = tail;
+    }
+    starting_with_move(path)
+}
+
+/// Build a path from element tags and the x, y coordinates of their points.
+fn decode_flat_commands(tags: &[u8], coords: &[f64]) -> Result<BezPath, LineSweeperError> {
+    let mut path = BezPath::new();
+    let mut rest = coords;
+    for (index, &tag) in tags.iter().enumerate() {
+        let Some(point_count) = tag_point_count(tag) else {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "unknown element tag {tag} at index {index}"
+            )));
+        };
+        let Some((values, tail)) = rest.split_at_checked(point_count * 2) else {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "ran out of coordinates at element {index}"
+            )));
+        };
+        let points: Vec<Point> =
+            values.chunks_exact(2).map(|xy| Point::new(xy[0], xy[1])).collect();
+        path.push(element_from_tag(tag, &points));
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(LineSweeperError::InvalidArgument(format!(
+            "{} coordinates left over after the last element",
+            rest.len()
+        )));
+    }
+    starting_with_move(path)
+}
+
+/// Convex hull of the flattened path, counterclockwise (y up).
+fn convex_hull(path: &BezPath) -> Vec<Point> {
+    convex_hull_of(&hull_points(path))
+}
+
+/// Vertices of the finely flattened path, sorted by x then y without duplicates.
+fn hull_points(path: &BezPath) -> Vec<Point> {
+    let points = fine_polylines(path).into_iter().flat_map(|polyline| polyline.points);
+    sorted_points(points.collect())
+}
+
+/// The path flattened finely enough for its hull to follow the curves.
+fn fine_polylines(path: &BezPath) -> Vec<Polyline> {
+    let bbox = path.bounding_box();
+    flatten_subpaths(path, bbox.width().hypot(bbox.height()) * 1e-6)
+}
+
+/// Points sorted by x then y, without duplicates.
+fn sorted_points(mut points: Vec<Point>) -> Vec<Point> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    points
+}
+
+/// Convex hull of points sorted as by `hull_points`, counterclockwise (y up).
+fn convex_hull_of(points: &[Point]) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    // Andrew's monotone chain; each half ends where the other starts
+    let mut hull = half_hull(points.iter());
+    hull.pop();
+    let mut upper = half_hull(points.iter().rev());
+    upper.pop();
+    hull.extend(upper);
+    hull
+}
+
+/// Most Fourier coefficients `BezierPath::shape_descriptor` returns.
+const SHAPE_DESCRIPTOR_MAX_BINS: u32 = 256;
+
+/// Most points sampled along the outline by `concave_hull`.
+const CONCAVE_HULL_MAX_SAMPLES: f64 = 4096.0;
+
+/// Concave hull of the flattened path, dug into edges longer than `alpha`.
+///
+/// The outline is sampled at most `alpha` apart, or coarser if that would
+/// take too many points. Starting from the convex hull of the samples,
+/// each edge longer than the spacing is replaced by two edges through the
+/// nearest sample inside that projects onto it, unless they would cross
+/// the hull. Samples on the edge come first, so straight runs are split up
+/// before they are dug into. No sample is left in the triangle cut away,
+/// since it would have been nearer. A non-positive or non-finite `alpha`
+/// gives the convex hull. Counterclockwise (y up).
+fn concave_hull(path: &BezPath, alpha: f64) -> Vec<Point> {
+    if !(alpha.is_finite() && alpha > 0.0) {
+        return convex_hull(path);
+    }
+    let polylines = fine_polylines(path);
+    let edges: Vec<(Point, Point)> = polylines
+        .iter()
+        .flat_map(|polyline| {
+            let ring = polyline.closed.then(|| polyline.points.first()).flatten();
+            let next = polyline.points.iter().skip(1).chain(ring);
+            polyline.points.iter().zip(next).map(|(a, b)| (*a, *b))
+        })
+        .collect();
+    let outline: f64 = edges.iter().map(|(a, b)| a.distance(*b)).sum();
+    let alpha = alpha.max(outline / CONCAVE_HULL_MAX_SAMPLES);
+    let mut samples: Vec<Point> = polylines.iter().flat_map(|p| p.points.clone()).collect();
+    for (a, b) in edges {
+        let pieces = (a.distance(b) / alpha).ceil().max(1.0) as usize;
+        samples.extend((1..pieces).map(|i| a.lerp(b, i as f64 / pieces as f64)));
+    }
+    let points = sorted_points(samples);
+
+    let mut hull = convex_hull_of(&points);
+    if hull.len() < 3 {
+        return hull;
+    }
+    let mut on_hull: Vec<bool> = points.iter().map(|p| hull.contains(p)).collect();
+    let mut i = 0;
+    while i < hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        let edge = b - a;
+        let length = edge.hypot();
+        let nearest = (length > alpha)
+            .then(|| {
+                let dist = |k: usize| {
+                    let v = points[k] - a;
+                    let t = v.dot(edge) / (length * length);
+                    let d = edge.cross(v) / length;
+                    ((0.0..=1.0).contains(&t) && d >= 0.0).then_some(d)
+                };
+                (0..points.len())
+                    .filter(|&k| !on_hull[k])
+                    .filter_map(|k| Some((k, dist(k)?)))
+                    .min_by(|x, y| x.1.total_cmp(&y.1))
+            })
+            .flatten();
+        let dig = nearest.filter(|&(k, _)| {
+            let p = points[k];
+            (0..hull.len()).filter(|&j| j != i).all(|j| {
+                let (c, d) = (hull[j], hull[(j + 1) % hull.len()]);
+                !segments_cross(a, p, c, d) && !segments_cross(p, b, c, d)
+            })
+        });
+        match dig {
+            // Stay on the new edge from `a`, which may need digging too
+            Some((k, _)) => {
+                on_hull[k] = true;
+                hull.insert(i + 1, points[k]);
+            }
+            None => i += 1,
+        }
+    }
+    hull
+}
+
+/// One half of a convex hull, over points sorted by x.
+fn half_hull<'a>(points: impl Iterator<Item = &'a Point>) -> Vec<Point> {
+    let mut hull: Vec<Point> = Vec::new();
+    for &p in points {
+        while let [.., a, b] = hull[..] {
+            if (b - a).cross(p - a) > 0.0 {
+                break;
+            }
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull
+}
+
+/// Minimize a unimodal function on `[low, high]` by golden-section search.
+fn golden_section_min(mut low: f64, mut high: f64, f: impl Fn(f64) -> f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    for _ in 0..60 {
+        let a = high - ratio * (high - low);
+        let b = low + ratio * (high - low);
+        if f(a) < f(b) {
+            high = b;
+        } else {
+            low = a;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Smallest apothem and its center for a regular polygon of given rotation containing `hull`.
+///
+/// The polygon's edge normals point at `theta + 2πk/sides`. For a fixed
+/// center the apothem is the largest support distance over those normals,
+/// which is convex in the center, so nested golden-section searches find it.
+fn fit_regular_polygon(hull: &[Point], sides: u32, theta: f64) -> (f64, Point) {
+    let period = std::f64::consts::TAU / sides as f64;
+    let normals: Vec<kurbo::Vec2> = (0..sides)
+        .map(|k| kurbo::Vec2::from_angle(theta + period * k as f64))
+        .collect();
+    let support: Vec<f64> = normals
+        .iter()
+        .map(|n| {
+            hull.iter()
+                .map(|p| p.to_vec2().dot(*n))
+                .fold(f64::NEG_INFINITY, f64::max)
+        })
+        .collect();
+    let apothem = |c: Point| {
+        normals
+            .iter()
+            .zip(&support)
+            .map(|(n, h)| h - c.to_vec2().dot(*n))
+            .fold(f64::NEG_INFINITY, f64::max)
+    };
+
+    let bbox = hull
+        .iter()
+        .fold(kurbo::Rect::from_points(hull[0], hull[0]), |r, p| r.union_pt(*p));
+    let best_y = |x: f64| golden_section_min(bbox.y0, bbox.y1, |y| apothem(Point::new(x, y)));
+    let x = golden_section_min(bbox.x0, bbox.x1, |x| apothem(Point::new(x, best_y(x))));
+    let center = Point::new(x, best_y(x));
+    (apothem(center), center)
+}
+
+/// Append `subpath` to `out` with its line-line corners filleted.
+///
+/// `should_round` is told whether each corner turns left and decides
+/// whether it gets rounded.
+fn round_subpath_corners(
+    subpath: &BezPath,
+    radius: f64,
+    should_round: impl Fn(bool) -> bool,
+    out: &mut BezPath,
+) {
+    let closed = subpath.elements().last() == Some(&PathEl::ClosePath);
+    let segments: Vec<PathSeg> = subpath.segments().collect();
+    let n = segments.len();
+    if n == 0 {
+        out.extend(subpath.iter());
+        return;
+    }
+
+    // The fillet after each segment, and how much it trims off both sides
+    let mut fillets: Vec<Option<CubicBez>> = vec![None; n];
+    let mut trim_start = vec![0.0; n];
+    let mut trim_end = vec![0.0; n];
+    let corner_count = if closed { n } else { n - 1 };
+    for i in 0..corner_count {
+        let j = (i + 1) % n;
+        let (PathSeg::Line(a), PathSeg::Line(b)) = (segments[i], segments[j]) else {
+            continue;
+        };
+        let (u_in, u_out) = (a.p1 - a.p0, b.p1 - b.p0);
+        let (len_in, len_out) = (u_in.hypot(), u_out.hypot());
+        if len_in == 0.0 || len_out == 0.0 {
+            continue;
+        }
+        let (u_in, u_out) = (u_in / len_in, u_out / len_out);
+        let turn = u_in.cross(u_out).atan2(u_in.dot(u_out));
+        let is_straight = turn.abs() < 1e-9;
+        let is_reversal = turn.abs() > std::f64::consts::PI - 1e-9;
+        if is_straight || is_reversal || !should_round(turn > 0.0) {
+            continue;
+        }
+
+        let half_tan = (turn.abs() / 2.0).tan();
+        let distance = (radius * half_tan).min(len_in / 2.0).min(len_out / 2.0);
+        let handle = 4.0 / 3.0 * (turn.abs() / 4.0).tan() * distance / half_tan;
+        let start = a.p1 - u_in * distance;
+        let end = a.p1 + u_out * distance;
+        fillets[i] = Some(CubicBez::new(start, start + u_in * handle, end - u_out * handle, end));
+        trim_end[i] = distance;
+        trim_start[j] = distance;
+    }
+
+    let trimmed = |i: usize| match segments[i] {
+        PathSeg::Line(line) => {
+            let u = (line.p1 - line.p0) / line.length().max(f64::MIN_POSITIVE);
+            let (p0, p1) = (line.p0 + u * trim_start[i], line.p1 - u * trim_end[i]);
+            PathSeg::Line(kurbo::Line::new(p0, p1))
+        }
+        seg => seg,
+    };
+    out.move_to(trimmed(0).start());
+    for (i, fillet) in fillets.iter().enumerate() {
+        // An implicit closing edge becomes explicit, as it may be trimmed
+        out.push(trimmed(i).as_path_el());
+        if let Some(fillet) = fillet {
+            out.curve_to(fillet.p1, fillet.p2, fillet.p3);
+        }
+    }
+    if closed {
+        out.close_path();
+    }
+}
+
+/// The parallel curve of a segment at a signed distance to its left.
+///
+/// Lines are offset exactly. Curves are fitted piecewise with cubics that
+/// match the offset's end points and tangents, split in half until every
+/// piece is within `accuracy` of the true offset at its quarter points.
+fn offset_seg(seg: PathSeg, distance: f64, accuracy: f64) -> BezPath {
+    let cubic = match seg {
+        PathSeg::Line(line) => {
+            let normal = (line.p1 - line.p0).normalize().turn_90();
+            let normal = if normal.is_finite() { normal } else { kurbo::Vec2::ZERO };
+            let mut path = BezPath::new();
+            path.move_to(line.p0 + normal * distance);
+            path.line_to(line.p1 + normal * distance);
+            return path;
+        }
+        PathSeg::Quad(quad) => quad.raise(),
+        PathSeg::Cubic(cubic) => cubic,
+    };
+    let deriv = cubic.deriv();
+    let second = deriv.deriv();
+    // The offset point and its derivative, o' = c' (1 - d·κ), at a parameter
+    let offset_at = |t: f64| {
+        let mut d1 = deriv.eval(t).to_vec2();
+        if d1.hypot() <= 1e-12 {
+            // At a cusp or a coincident control point, look just inside
+            d1 = deriv.eval(t.clamp(1e-6, 1.0 - 1e-6)).to_vec2();
+        }
+        let speed = d1.hypot();
+        let curvature = d1.cross(second.eval(t).to_vec2()) / (speed * speed * speed);
+        let point = cubic.eval(t) + d1.turn_90() * (distance / speed);
+        (point, d1 * (1.0 - distance * curvature))
+    };
+
+    let mut path = BezPath::new();
+    path.move_to(offset_at(0.0).0);
+    let mut pieces = vec![(0.0, 1.0, 0)];
+    while let Some((t0, t1, depth)) = pieces.pop() {
+        let ((p0, d0), (p3, d3)) = (offset_at(t0), offset_at(t1));
+        let span = (t1 - t0) / 3.0;
+        let fit = CubicBez::new(p0, p0 + d0 * span, p3 - d3 * span, p3);
+        let error = [0.25, 0.5, 0.75]
+            .iter()
+            .map(|&s| fit.eval(s).distance(offset_at(t0 + s * (t1 - t0)).0))
+            .fold(0.0, f64::max);
+        if error <= accuracy || depth >= 16 {
+            path.curve_to(fit.p1, fit.p2, fit.p3);
+        } else {
+            // Later pieces go on the stack first so they are emitted in order
+            let tm = 0.5 * (t0 + t1);
+            pieces.push((tm, t1, depth + 1));
+            pieces.push((t0, tm, depth + 1));
+        }
+    }
+    path
+}
+
+/// Miter limit at the outer corners of `BezierPath::rails`, as a multiple of
+/// the half width, the same as kurbo's default for strokes.
+const RAIL_MITER_LIMIT: f64 = 4.0;
+
+/// One rail of a path: every subpath offset by `distance`, with corners joined.
+///
+/// Outer corners are mitered, or beveled where the miter would reach past
+/// `RAIL_MITER_LIMIT` times the distance. At inner corners two lines are
+/// cut back to where they cross; other pieces are joined by a line.
+fn rail(path: &BezPath, distance: f64, accuracy: f64) -> BezPath {
+    let mut rail = BezPath::new();
+    for subpath in split_subpaths(path) {
+        let closed = matches!(subpath.elements().last(), Some(PathEl::ClosePath));
+        let segments: Vec<PathSeg> = subpath
+            .segments()
+            .filter(|seg| start_direction(seg).is_some())
+            .collect();
+        let Some(first) = segments.first() else {
+            continue;
+        };
+        let end_direction = |seg: &PathSeg| -start_direction(&seg.reverse()).unwrap_or_default();
+        let start_direction = |seg: &PathSeg| start_direction(seg).unwrap_or_default();
+
+        let mut els: Vec<PathEl> = offset_seg(*first, distance, accuracy).elements().to_vec();
+        for pair in segments.windows(2) {
+            let next = offset_seg(pair[1], distance, accuracy);
+            let q = next.elements()[0].end_point().unwrap_or_default();
+            let corner = (end_direction(&pair[0]), start_direction(&pair[1]));
+            let rest = &next.elements()[1..];
+            match rail_corner(&els, corner, q, distance) {
+                RailCorner::Miter(x) => els.extend([PathEl::LineTo(x), PathEl::LineTo(q)]),
+                RailCorner::Cross(x) if matches!(rest.first(), Some(PathEl::LineTo(_))) => {
+                    *els.last_mut().unwrap() = PathEl::LineTo(x);
+                }
+                _ => els.push(PathEl::LineTo(q)),
+            }
+            els.extend_from_slice(rest);
+        }
+        if closed {
+            let q = els[0].end_point().unwrap_or_default();
+            let last = segments.last().unwrap_or(first);
+            let corner = (end_direction(last), start_direction(first));
+            match rail_corner(&els, corner, q, distance) {
+                RailCorner::Miter(x) => els.push(PathEl::LineTo(x)),
+                RailCorner::Cross(x) if matches!(els.get(1), Some(PathEl::LineTo(_))) => {
+                    *els.last_mut().unwrap() = PathEl::LineTo(x);
+                    els[0] = PathEl::MoveTo(x);
+                }
+                _ => {}
+            }
+            els.push(PathEl::ClosePath);
+        }
+        rail.extend(els);
+    }
+    rail
+}
+
+/// How two consecutive pieces of a rail meet, from `rail_corner`.
+enum RailCorner {
+    /// The pieces already meet
+    Meet,
+    /// An outer corner, mitered at the given point
+    Miter(Point),
+    /// An outer corner whose miter is too long, or a reversal
+    Bevel,
+    /// An inner corner, where the last line crosses the next piece's tangent at the point
+    Cross(Point),
+}
+
+/// Classify the corner between the rail built so far and a piece starting at `q`.
+///
+/// The directions are the tangents at the end of the previous piece and at
+/// the start of the next one.
+fn rail_corner(
+    els: &[PathEl],
+    (end_dir, start_dir): (kurbo::Vec2, kurbo::Vec2),
+    q: Point,
+    distance: f64,
+) -> RailCorner {
+    let p = els.last().and_then(|el| el.end_point()).unwrap_or_default();
+    if p.distance(q) <= distance.abs() * 1e-9 {
+        return RailCorner::Meet;
+    }
+    let denom = end_dir.cross(start_dir);
+    if denom.abs() <= 1e-12 {
+        return RailCorner::Bevel;
+    }
+    // The tangent lines meet at x = p + end_dir·u = q + start_dir·v
+    let u = (q - p).cross(start_dir) / denom;
+    let x = p + end_dir * u;
+    let v = (x - q).dot(start_dir);
+    if u > 0.0 && v < 0.0 {
+        if u <= RAIL_MITER_LIMIT * distance.abs() {
+            RailCorner::Miter(x)
+        } else {
+            RailCorner::Bevel
+        }
+    } else if u < 0.0 && v > 0.0 {
+        // Only cut the last line back if the crossing is still on it
+        let line_start = els.len().checked_sub(2).and_then(|i| els[i].end_point());
+        match (els.last(), line_start) {
+            (Some(PathEl::LineTo(_)), Some(s)) if (x - s).dot(end_dir) > 0.0 => {
+                RailCorner::Cross(x)
+            }
+            _ => RailCorner::Bevel,
+        }
+    } else {
+        RailCorner::Bevel
+    }
+}
+
+/// Check that every coordinate of a path is finite, as the sweep requires.
+fn check_finite(path: &BezPath) -> Result<(), LineSweeperError> {
+    let points = path.elements().iter().flat_map(|el| match *el {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => vec![p],
+        PathEl::QuadTo(p1, p2) => vec![p1, p2],
+        PathEl::CurveTo(p1, p2, p3) => vec![p1, p2, p3],
+        PathEl::ClosePath => vec![],
+    });
+    check_points_finite(points)
+}
+
+/// Check that every coordinate of some points is finite.
+fn check_points_finite(points: impl IntoIterator<Item = Point>) -> Result<(), LineSweeperError> {
+    for p in points {
+        if p.is_nan() {
+            return Err(LineSweeperError::NaNInput);
+        }
+        if !p.is_finite() {
+            return Err(LineSweeperError::InfiniteInput);
+        }
+    }
+    Ok(())
+}
+
+/// Bounding box of a segment's control points, which contains the segment.
+fn control_box(seg: &PathSeg) -> kurbo::Rect {
+    let points = seg_points(seg);
+    points[1..]
+        .iter()
+        .fold(kurbo::Rect::from_points(points[0], points[0]), |r, p| r.union_pt(*p))
+}
+
+/// Parameters `(ta, tb)` where segments `a` and `b` meet, to within `tolerance`.
+///
+/// Lines are intersected exactly; curves by recursive subdivision. Segments
+/// that overlap along a stretch report a limited number of points on it.
+fn segment_intersections(a: &PathSeg, b: &PathSeg, tolerance: f64) -> Vec<(f64, f64)> {
+    if let (PathSeg::Line(a), PathSeg::Line(b)) = (a, b) {
+        let (da, db) = (a.p1 - a.p0, b.p1 - b.p0);
+        let denom = da.cross(db);
+        if denom == 0.0 {
+            return Vec::new();
+        }
+        let w = b.p0 - a.p0;
+        let (ta, tb) = (w.cross(db) / denom, w.cross(da) / denom);
+        let slack = 1e-12;
+        let in_range = |t: f64| (-slack..=1.0 + slack).contains(&t);
+        return if in_range(ta) && in_range(tb) {
+            vec![(ta.clamp(0.0, 1.0), tb.clamp(0.0, 1.0))]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let mut hits = Vec::new();
+    let mut budget = 4096;
+    subdivide_intersections(*a, (0.0, 1.0), *b, (0.0, 1.0), tolerance, &mut budget, &mut hits);
+
+    // Subdivision finds clusters of nearby hits; keep one of each
+    hits.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let mut unique: Vec<(f64, f64)> = Vec::new();
+    for hit in hits {
+        let point = a.eval(hit.0);
+        if unique
+            .iter()
+            .all(|other| a.eval(other.0).distance(point) > tolerance * 16.0)
+        {
+            unique.push(hit);
+        }
+    }
+    unique
+}
+
+fn subdivide_intersections(
+    a: PathSeg,
+    a_range: (f64, f64),
+    b: PathSeg,
+    b_range: (f64, f64),
+    tolerance: f64,
+    budget: &mut usize,
+    hits: &mut Vec<(f64, f64)>,
+) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
+    let (box_a, box_b) = (control_box(&a), control_box(&b));
+    let overlaps = box_a.x0 <= box_b.x1 + tolerance
+        && box_b.x0 <= box_a.x1 + tolerance
+        && box_a.y0 <= box_b.y1 + tolerance
+        && box_b.y0 <= box_a.y1 + tolerance;
+    if !overlaps {
+        return;
+    }
+    let size = |r: kurbo::Rect| r.width().hypot(r.height());
+    if size(box_a).max(size(box_b)) <= tolerance {
+        let mid = |(t0, t1): (f64, f64)| 0.5 * (t0 + t1);
+        hits.push((mid(a_range), mid(b_range)));
+        return;
+    }
+
+    let halves = |seg: PathSeg, (t0, t1): (f64, f64)| {
+        let tm = 0.5 * (t0 + t1);
+        [
+            (seg.subsegment(0.0..0.5), (t0, tm)),
+            (seg.subsegment(0.5..1.0), (tm, t1)),
+        ]
+    };
+    for (a_half, a_half_range) in halves(a, a_range) {
+        for (b_half, b_half_range) in halves(b, b_range) {
+            subdivide_intersections(
+                a_half,
+                a_half_range,
+                b_half,
+                b_half_range,
+                tolerance,
+                budget,
+                hits,
+            );
+        }
+    }
+}
+
+/// Whether any two segments of a finite path meet, other than neighbours at their shared vertex.
+fn self_intersects(path: &BezPath) -> bool {
+    let bbox = path.bounding_box();
+    let tolerance = bbox.width().hypot(bbox.height()) * 1e-9;
+    let segments: Vec<Vec<PathSeg>> = split_subpaths(path)
+        .iter()
+        .map(|subpath| subpath.segments().collect())
+        .collect();
+    let crossings = self_intersection_params(&segments, tolerance);
+    crossings.iter().flatten().any(|params| !params.is_empty())
+}
+
+/// For each segment of each subpath, the parameters where it meets another.
+///
+/// Neighbouring segments of a subpath are not counted as meeting at the
+/// vertex they share.
+fn self_intersection_params(subpaths: &[Vec<PathSeg>], tolerance: f64) -> Vec<Vec<Vec<f64>>> {
+    let segments: Vec<(usize, usize, PathSeg)> = subpaths
+        .iter()
+        .enumerate()
+        .flat_map(|(i, segs)| segs.iter().enumerate().map(move |(j, seg)| (i, j, *seg)))
+        .collect();
+    let mut params: Vec<Vec<Vec<f64>>> = subpaths
+        .iter()
+        .map(|segs| vec![Vec::new(); segs.len()])
+        .collect();
+
+    for (k, &(i, j, a)) in segments.iter().enumerate() {
+        for &(i2, j2, b) in &segments[k + 1..] {
+            let n = subpaths[i].len();
+            let shared_vertex = if i != i2 {
+                None
+            } else if j2 == j + 1 {
+                Some(a.end())
+            } else if j == 0 && j2 == n - 1 && b.end() == a.start() {
+                Some(a.start())
+            } else {
+                None
+            };
+            for (ta, tb) in segment_intersections(&a, &b, tolerance) {
+                let point = a.eval(ta);
+                if shared_vertex.is_some_and(|v| v.distance(point) <= tolerance * 1e3) {
+                    continue;
+                }
+                params[i][j].push(ta);
+                params[i2][j2].push(tb);
+            }
+        }
+    }
+    params
+}
+
+/// Cut a subpath's segments at the given parameters into open paths.
+///
+/// If a closed subpath isn't cut at its start, the pieces before and after
+/// the start are joined.
+fn split_at_params(segments: &[PathSeg], params: &[Vec<f64>]) -> Vec<BezPath> {
+    let Some(first) = segments.first() else {
+        return Vec::new();
+    };
+    let eps = 1e-9;
+    let mut pieces: Vec<BezPath> = Vec::new();
+    let mut current = BezPath::new();
+    current.move_to(first.start());
+    let flush = |current: &mut BezPath, pieces: &mut Vec<BezPath>| {
+        if current.elements().len() > 1 {
+            let end = current.elements().last().and_then(|el| el.end_point());
+            pieces.push(std::mem::take(current));
+            current.move_to(end.unwrap_or_default());
+        }
+    };
+
+    let mut cut_at_start = false;
+    for (i, seg) in segments.iter().enumerate() {
+        let mut ts = params[i].clone();
+        ts.sort_by(f64::total_cmp);
+        let mut t0 = 0.0;
+        let mut cut_after = false;
+        for t in ts {
+            if t <= eps {
+                cut_at_start |= i == 0;
+                flush(&mut current, &mut pieces);
+            } else if t >= 1.0 - eps {
+                cut_after = true;
+            } else if t - t0 > eps {
+                current.push(seg.subsegment(t0..t).as_path_el());
+                flush(&mut current, &mut pieces);
+                t0 = t;
+            }
+        }
+        current.push(seg.subsegment(t0..1.0).as_path_el());
+        if cut_after {
+            cut_at_start |= i == segments.len() - 1;
+            flush(&mut current, &mut pieces);
+        }
+    }
+    flush(&mut current, &mut pieces);
+
+    let last = segments[segments.len() - 1];
+    let closed = last.end() == first.start();
+    if closed && !cut_at_start && pieces.len() > 1 {
+        let head = pieces.remove(0);
+        if let Some(tail) = pieces.last_mut() {
+            tail.extend(head.elements()[1..].iter().copied());
+        }
+    }
+    pieces
+}
+
+/// A closed polygon with vertices at equally spaced angles and the given radii.
+fn radial_polygon(center: Point, radii: &[f64], rotation: f64) -> BezPath {
+    let mut path = BezPath::new();
+    for (i, radius) in radii.iter().enumerate() {
+        let angle = rotation + std::f64::consts::TAU * i as f64 / radii.len() as f64;
+        let vertex = center + kurbo::Vec2::from_angle(angle) * *radius;
+        if i == 0 {
+            path.move_to(vertex);
+        } else {
+            path.line_to(vertex);
+        }
+    }
+    if !radii.is_empty() {
+        path.close_path();
+    }
+    path
+}
+
+/// Whether any subpath of a path doesn't end with a `ClosePath`.
+fn has_open_subpaths(path: &BezPath) -> bool {
+    let elements = path.elements();
+    elements.iter().enumerate().any(|(i, el)| {
+        let ends_subpath = matches!(elements.get(i + 1), None | Some(PathEl::MoveTo(_)));
+        ends_subpath && *el != PathEl::ClosePath
+    })
+}
+
+/// Close every open subpath with a line back to its start.
+fn close_subpaths(path: &BezPath) -> BezPath {
+    split_subpaths(path)
+        .into_iter()
+        .flat_map(|mut subpath| {
+            if subpath.elements().last() != Some(&PathEl::ClosePath) {
+                subpath.close_path();
+            }
+            subpath
+        })
+        .collect()
+}
+
+/// Separate a path's closed subpaths, kept together, from its open ones.
+fn partition_open_subpaths(path: &BezPath) -> (BezPath, Vec<BezPath>) {
+    let mut closed = BezPath::new();
+    let mut open = Vec::new();
+    for subpath in split_subpaths(path) {
+        if matches!(subpath.elements().last(), Some(PathEl::ClosePath)) {
+            closed.extend(subpath);
+        } else {
+            open.push(subpath);
+        }
+    }
+    (closed, open)
+}
+
+/// Format a path as SVG path data, optionally rounding coordinates.
+///
+/// Relative commands give each point as an offset from the rounded position
+/// of the previous one, so rounding errors don't accumulate along the path.
+fn svg_path_data(path: &BezPath, decimal_places: Option<u8>, relative: bool) -> String {
+    let scale = decimal_places.map(|places| 10f64.powi(places.into()));
+    let round = |v: f64| match scale {
+        // Adding zero turns a rounded -0 into 0
+        Some(scale) => (v * scale).round() / scale + 0.0,
+        None => v,
+    };
+    let letter = |command: char| {
+        if relative {
+            command.to_ascii_lowercase()
+        } else {
+            command
+        }
+    };
+
+    let mut commands = Vec::new();
+    let (mut start, mut current) = (Point::ZERO, Point::ZERO);
+    for el in path.elements() {
+        let el = map_points(*el, |p| Point::new(round(p.x), round(p.y)));
+        let origin = if relative { current } else { Point::ZERO };
+        let point = |p: Point| format!("{},{}", round(p.x - origin.x), round(p.y - origin.y));
+        commands.push(match el {
+            PathEl::MoveTo(p) => format!("{}{}", letter('M'), point(p)),
+            PathEl::LineTo(p) => format!("{}{}", letter('L'), point(p)),
+            PathEl::QuadTo(p1, p2) => format!("{}{} {}", letter('Q'), point(p1), point(p2)),
+            PathEl::CurveTo(p1, p2, p3) => {
+                format!("{}{} {} {}", letter('C'), point(p1), point(p2), point(p3))
+            }
+            PathEl::ClosePath => letter('Z').to_string(),
+        });
+        if let PathEl::MoveTo(p) = el {
+            start = p;
+        }
+        current = el.end_point().unwrap_or(start);
+    }
+    commands.join(" ")
+}
+
+/// Whether two paths have the same elements, with points within `tolerance`.
+fn elements_approx_eq(a: &BezPath, b: &BezPath, tolerance: f64) -> bool {
+    elements_match(a, b, |p, q| p.distance(*q) <= tolerance)
+}
+
+/// Whether two paths have the same elements, with points that `close` accepts.
+fn elements_match(a: &BezPath, b: &BezPath, close: impl Fn(&Point, &Point) -> bool) -> bool {
+    a.elements().len() == b.elements().len()
+        && a.elements().iter().zip(b.elements()).all(|pair| match pair {
+            (PathEl::MoveTo(p), PathEl::MoveTo(q)) | (PathEl::LineTo(p), PathEl::LineTo(q)) => {
+                close(p, q)
+            }
+            (PathEl::QuadTo(p1, p2), PathEl::QuadTo(q1, q2)) => close(p1, q1) && close(p2, q2),
+            (PathEl::CurveTo(p1, p2, p3), PathEl::CurveTo(q1, q2, q3)) => {
+                close(p1, q1) && close(p2, q2) && close(p3, q3)
+            }
+            (PathEl::ClosePath, PathEl::ClosePath) => true,
+            _ => false,
+        })
+}
+
+/// Most lines `split_curves` replaces a single curve with.
+const SPLIT_CURVES_MAX_PIECES: usize = 4096;
+
+/// Replace each curve with `pieces(curve)` lines.
+///
+/// The lines split the curve evenly in parameter, or in arc length to
+/// within `arclen_accuracy` when it is given. The count is clamped to
+/// `SPLIT_CURVES_MAX_PIECES`, so that no request can exhaust memory.
+fn split_curves(
+    path: &BezPath,
+    pieces: impl Fn(&PathSeg) -> usize,
+    arclen_accuracy: Option<f64>,
+) -> BezPath {
+    let mut result = BezPath::new();
+    let (mut start, mut last) = (Point::ZERO, Point::ZERO);
+    for el in path.elements() {
+        let seg = match *el {
+            PathEl::QuadTo(p1, p2) => Some(PathSeg::Quad(kurbo::QuadBez::new(last, p1, p2))),
+            PathEl::CurveTo(p1, p2, p3) => Some(PathSeg::Cubic(CubicBez::new(last, p1, p2, p3))),
+            _ => None,
+        };
+        match seg {
+            Some(seg) => {
+                let n = pieces(&seg).clamp(1, SPLIT_CURVES_MAX_PIECES);
+                let arclen = arclen_accuracy.map(|accuracy| (seg.arclen(accuracy), accuracy));
+                for i in 1..n {
+                    let fraction = i as f64 / n as f64;
+                    let t = match arclen {
+                        Some((total, accuracy)) => seg.inv_arclen(total * fraction, accuracy),
+                        None => fraction,
+                    };
+                    result.line_to(seg.eval(t));
+                }
+                result.line_to(seg.end());
+            }
+            None => result.push(*el),
+        }
+        if let PathEl::MoveTo(p) = el {
+            start = *p;
+        }
+        last = el.end_point().unwrap_or(start);
+    }
+    result
+}
+
+/// Signed area, ∫ x dA, ∫ y dA, ∫ y² dA, ∫ x² dA and ∫ x·y dA of a ring.
+fn ring_moments(ring: &[Point]) -> [f64; 6] {
+    let mut sums = [0.0; 6];
+    for (i, p) in ring.iter().enumerate() {
+        let q = ring[(i + 1) % ring.len()];
+        let cross = p.x * q.y - q.x * p.y;
+        sums[0] += cross / 2.0;
+        sums[1] += (p.x + q.x) * cross / 6.0;
+        sums[2] += (p.y + q.y) * cross / 6.0;
+        sums[3] += (p.y * p.y + p.y * q.y + q.y * q.y) * cross / 12.0;
+        sums[4] += (p.x * p.x + p.x * q.x + q.x * q.x) * cross / 12.0;
+        sums[5] += (p.x * q.y + 2.0 * p.x * p.y + 2.0 * q.x * q.y + q.x * p.y) * cross / 24.0;
+    }
+    sums
+}
+
+// Create a wrapper for the result since UniFFI doesn't support Vec<Object>
+#[derive(uniffi::Record)]
+pub struct BooleanOperationResult {
+    pub paths: Vec<std::sync::Arc<BezierPath>>,
+}
+
+/// Perform a boolean operation on the regions two paths fill
+///
+/// Open subpaths are closed first. An operand with no segments fills
+/// nothing: the union, the xor and the difference with it as the second
+/// operand give the other operand's filled region, while the intersection
+/// and the difference with it as the first operand are empty.
+#[uniffi::export]
+pub fn boolean_operation(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let (kurbo_a, kurbo_b) = prepare_operands(path_a, path_b);
+    prepared_boolean(&kurbo_a, &kurbo_b, operation, fill_rule, &mut None)
+}
+
+/// Convert both operands to kurbo paths, closing open subpaths so that they
+/// enclose the region they would fill.
+fn prepare_operands(path_a: &BezierPath, path_b: &BezierPath) -> (BezPath, BezPath) {
+    (close_subpaths(&path_a.to_kurbo_path()), close_subpaths(&path_b.to_kurbo_path()))
+}
+
+/// Perform a boolean operation on operands from `prepare_operands`.
+///
+/// Operations whose result can be read off the operands skip the sweep.
+/// Otherwise the arrangement of both operands is built into `topology` the
+/// first time it is needed, and reused by later calls on the same operands.
+fn prepared_boolean(
+    kurbo_a: &BezPath,
+    kurbo_b: &BezPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+    topology: &mut Option<Topology<BinaryWindingNumber>>,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    if let Some(result) = empty_operand_boolean(kurbo_a, kurbo_b, operation) {
+        return Ok(result);
+    }
+    if let Some(result) = disjoint_boolean(kurbo_a, kurbo_b, operation) {
+        return Ok(result);
+    }
+    let tolerance = sweep_epsilon(kurbo_a, kurbo_b)?;
+    let topology = match topology {
+        Some(topology) => topology,
+        None => topology.insert(sweep_with_tolerance(kurbo_a, kurbo_b, tolerance)?),
+    };
+    let result = extract_operation(topology, operation, fill_rule)?;
+    restore_input_points(&result, &[kurbo_a, kurbo_b], tolerance);
+    Ok(result)
+}
+
+/// The result of a boolean operation where an operand has no segments.
+///
+/// Such an operand fills nothing, so intersecting with it or subtracting
+/// from it gives nothing, and any other operation gives the filled region
+/// of the other operand. That region is read off the operand when it is
+/// already made of simple contours, as in `disjoint_boolean`; otherwise
+/// `None` is returned so that the sweep computes it.
+fn empty_operand_boolean(
+    path_a: &BezPath,
+    path_b: &BezPath,
+    operation: BooleanOperation,
+) -> Option<BooleanOperationResult> {
+    if check_finite(path_a).is_err() || check_finite(path_b).is_err() {
+        return None;
+    }
+    let eps = sweep_epsilon(path_a, path_b).ok()?;
+    let is_empty = |path: &BezPath| path.segments().next().is_none();
+    let kept = match (is_empty(path_a), is_empty(path_b), operation) {
+        (false, false, _) => return None,
+        (_, _, BooleanOperation::Intersection) => None,
+        (true, _, BooleanOperation::Difference) => None,
+        (true, _, _) => Some(path_b),
+        (false, true, _) => Some(path_a),
+    };
+    let paths = match kept {
+        Some(path) => simple_contours(path, eps)?
+            .into_iter()
+            .map(|contour| Arc::new(BezierPath::from_kurbo_path(contour)))
+            .collect(),
+        None => Vec::new(),
+    };
+    Some(BooleanOperationResult { paths })
+}
+
+/// The result of a boolean operation whose operands' bounding boxes are apart.
+///
+/// Returns `None`, so that the sweep runs, unless the result can be read off
+/// the operands: nothing for an intersection, or else the subpaths of the
+/// operands the operation keeps, when they are already separate simple
+/// contours that the sweep would return unchanged. The boxes must be
+/// further apart than the sweep epsilon, since the sweep merges anything
+/// closer.
+fn disjoint_boolean(
+    path_a: &BezPath,
+    path_b: &BezPath,
+    operation: BooleanOperation,
+) -> Option<BooleanOperationResult> {
+    if check_finite(path_a).is_err() || check_finite(path_b).is_err() {
+        return None;
+    }
+    let eps = sweep_epsilon(path_a, path_b).ok()?;
+    let box_a = path_a.bounding_box().inflate(eps, eps);
+    let box_b = path_b.bounding_box().inflate(eps, eps);
+    let apart = box_a.x1 < box_b.x0
+        || box_b.x1 < box_a.x0
+        || box_a.y1 < box_b.y0
+        || box_b.y1 < box_a.y0;
+    if !apart {
+        return None;
+    }
+    let kept: &[&BezPath] = match operation {
+        BooleanOperation::Intersection => &[],
+        BooleanOperation::Difference => &[path_a],
+        BooleanOperation::Union | BooleanOperation::Xor => &[path_a, path_b],
+    };
+    let mut paths = Vec::new();
+    for path in kept {
+        let contours = simple_contours(path, eps)?;
+        paths.extend(contours.into_iter().map(|c| Arc::new(BezierPath::from_kurbo_path(c))));
+    }
+    Some(BooleanOperationResult { paths })
+}
+
+/// The closed subpaths of a path, if none encloses, crosses or comes within
+/// `eps` of another.
+///
+/// Such subpaths fill exactly their own interiors under either fill rule,
+/// so they are the path's filled region as it is.
+fn simple_contours(path: &BezPath, eps: f64) -> Option<Vec<BezPath>> {
+    let subpaths = split_subpaths(path);
+    if subpaths.iter().any(is_sliver) {
+        return None;
+    }
+    if subpath_parents(&subpaths).iter().any(Option::is_some) {
+        return None;
+    }
+    let segments: Vec<Vec<PathSeg>> =
+        subpaths.iter().map(|subpath| subpath.segments().collect()).collect();
+    let crossings = self_intersection_params(&segments, eps);
+    if crossings.iter().flatten().any(|params| !params.is_empty()) {
+        return None;
+    }
+    Some(subpaths)
+}
+
+/// Perform a boolean operation with an explicit sweep tolerance
+///
+/// `tolerance` is in the same units as the path coordinates: geometry
+/// closer together than that may be merged, which removes the slivers left
+/// between nearly coincident edges. `boolean_operation` picks a tolerance
+/// just above floating-point noise for the size of its inputs. Returns an
+/// `InvalidArgument` error unless `tolerance` is positive and finite.
+#[uniffi::export]
+pub fn boolean_operation_with_tolerance(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+    tolerance: f64,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    if !(tolerance.is_finite() && tolerance > 0.0) {
+        return Err(LineSweeperError::InvalidArgument(format!(
+            "tolerance must be positive and finite, got {tolerance}"
+        )));
+    }
+    let (kurbo_a, kurbo_b) = prepare_operands(path_a, path_b);
+    let topology = sweep_with_tolerance(&kurbo_a, &kurbo_b, tolerance)?;
+    let result = extract_operation(&topology, operation, fill_rule)?;
+    // Only floating-point noise is undone; points merged by a larger
+    // tolerance stay where the sweep put them
+    restore_input_points(&result, &[&kurbo_a, &kurbo_b], sweep_epsilon(&kurbo_a, &kurbo_b)?);
+    Ok(result)
+}
+
+/// The result of `boolean_operation_keep_open`
+#[derive(uniffi::Record)]
+pub struct KeepOpenResult {
+    /// The boolean operation over the closed subpaths of both operands
+    pub result: BooleanOperationResult,
+    /// The open subpaths of both operands, unchanged, those of `path_a` first
+    pub open_paths: Vec<Arc<BezierPath>>,
+}
+
+/// Perform a boolean operation with a separate fill rule for each operand
+///
+/// Each operand is first resolved into the region it fills under its own
+/// rule, as by `simplify`, and the two regions are then combined. This
+/// allows, say, a nonzero outline to be cut by an even-odd stamp.
+#[uniffi::export]
+pub fn boolean_operation_dual_rule(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule_a: FillRule,
+    fill_rule_b: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let region_a = BezierPath::from_kurbo_path(filled_region(path_a, fill_rule_a)?);
+    let region_b = BezierPath::from_kurbo_path(filled_region(path_b, fill_rule_b)?);
+    // The regions don't overlap themselves, so nonzero keeps them as they are
+    boolean_operation(&region_a, &region_b, operation, FillRule::NonZero)
+}
+
+/// Perform a boolean operation, passing open subpaths through untouched
+///
+/// Unlike `boolean_operation`, which closes open subpaths, only the closed
+/// subpaths contribute to the fill; the open ones, such as guide strokes
+/// drawn alongside the shapes, are returned separately in `open_paths`.
+#[uniffi::export]
+pub fn boolean_operation_keep_open(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+) -> Result<KeepOpenResult, LineSweeperError> {
+    let (closed_a, open_a) = partition_open_subpaths(&path_a.to_kurbo_path());
+    let (closed_b, open_b) = partition_open_subpaths(&path_b.to_kurbo_path());
+    let result = checked_binary_op(
+        &closed_a,
+        &closed_b,
+        LsFillRule::from(fill_rule),
+        BinaryOp::from(operation),
+    )?;
+    let open_paths = open_a
+        .into_iter()
+        .chain(open_b)
+        .map(|path| Arc::new(BezierPath::from_kurbo_path(path)))
+        .collect();
+    let result = boolean_result(result);
+    restore_input_points(&result, &[&closed_a, &closed_b], sweep_epsilon(&closed_a, &closed_b)?);
+    Ok(KeepOpenResult { result, open_paths })
+}
+
+/// Perform a boolean operation, choosing how open subpaths are handled
+///
+/// With `close_open_subpaths`, subpaths that don't end with a `ClosePath`
+/// get a closing line back to their start, exactly as in
+/// `boolean_operation`. Otherwise they are left out of the operation.
+#[uniffi::export]
+pub fn boolean_operation_with_open_subpaths(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+    close_open_subpaths: bool,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    if close_open_subpaths {
+        boolean_operation(path_a, path_b, operation, fill_rule)
+    } else {
+        Ok(boolean_operation_keep_open(path_a, path_b, operation, fill_rule)?.result)
+    }
+}
+
+/// Put result vertices back exactly where the input vertices they came from were.
+///
+/// The sweep can perturb coordinates slightly even away from intersections,
+/// so any segment end point within `eps` of an input end point is replaced
+/// by the nearest one, leaving untouched regions bit-identical. Control
+/// points are left alone, so a vertex is never snapped onto one.
+fn restore_input_points(result: &BooleanOperationResult, inputs: &[&BezPath], eps: f64) {
+    let mut points: Vec<Point> = inputs
+        .iter()
+        .flat_map(|path| path.elements())
+        .filter_map(|el| el.end_point())
+        .collect();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+    let restore = |p: Point| {
+        let first = points.partition_point(|q| q.x < p.x - eps);
+        points[first..]
+            .iter()
+            .take_while(|q| q.x <= p.x + eps)
+            .map(|q| (q.distance(p), *q))
+            .filter(|(distance, _)| *distance <= eps)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(p, |(_, q)| q)
+    };
+    for path in &result.paths {
+        let mut path = lock(&path.path);
+        let restored: BezPath = path.iter().map(|el| map_end_point(el, restore)).collect();
+        *path = restored;
+    }
+}
+
+/// Filled area of a boolean operation result, with holes subtracted
+fn result_area(result: &BooleanOperationResult) -> f64 {
+    let contours: Vec<BezPath> = result.paths.iter().map(|p| p.to_kurbo_path()).collect();
+    filled_area(&contours)
+}
+
+fn boolean_result(contours: Contours) -> BooleanOperationResult {
+    let paths = convert_contours_to_paths(contours)
+        .into_iter()
+        .map(std::sync::Arc::new)
+        .collect();
+
+    BooleanOperationResult { paths }
+}
+
+/// Pick the sweep epsilon `binary_op` would use for two paths, rejecting non-finite input.
+fn sweep_epsilon(path_a: &BezPath, path_b: &BezPath) -> Result<f64, LineSweeperError> {
+    let bbox = path_a.bounding_box().union(path_b.bounding_box());
+    let min = bbox.x0.min(bbox.y0);
+    let max = bbox.x1.max(bbox.y1);
+    if min.is_nan() || max.is_nan() {
+        return Err(LineSweeperError::NaNInput);
+    }
+    if min.is_infinite() || max.is_infinite() {
+        return Err(LineSweeperError::InfiniteInput);
+    }
+    Ok((min.abs().max(max.abs()) * (f64::EPSILON * 64.0)).max(1e-6))
+}
+
+/// Build the sweep-line arrangement of two paths with a given epsilon.
+fn sweep_with_tolerance(
+    path_a: &BezPath,
+    path_b: &BezPath,
+    eps: f64,
+) -> Result<Topology<BinaryWindingNumber>, LineSweeperError> {
+    check_finite(path_a)?;
+    check_finite(path_b)?;
+    catch_engine_panic(|| Topology::from_paths_binary(path_a, path_b, eps))?
+        .map_err(LineSweeperError::from)
+}
+
+fn extract_operation(
+    topology: &Topology<BinaryWindingNumber>,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let contours = catch_engine_panic(|| {
+        topology.contours(|w| {
+            operation.combine(fill_rule.is_inside(w.shape_a), fill_rule.is_inside(w.shape_b))
+        })
+    })?;
+    Ok(boolean_result(contours))
+}
+
+/// Run `binary_op`, turning a panic inside linesweeper into an error
+fn checked_binary_op(
+    set_a: &BezPath,
+    set_b: &BezPath,
+    fill_rule: LsFillRule,
+    op: BinaryOp,
+) -> Result<Contours, LineSweeperError> {
+    catch_engine_panic(|| binary_op(set_a, set_b, fill_rule, op))?.map_err(LineSweeperError::from)
+}
+
+/// Call into linesweeper, catching any panic before it unwinds across the FFI
+///
+/// A panic that reaches the JVM aborts the whole process, so a caught panic
+/// becomes an `InternalError` carrying the panic message instead.
+fn catch_engine_panic<T>(f: impl FnOnce() -> T) -> Result<T, LineSweeperError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        LineSweeperError::InternalError(format!("linesweeper panicked: {message}"))
+    })
+}
+
+/// Orientation to give the contours of a boolean operation result
+#[derive(uniffi::Enum, Clone, Copy)]
+pub enum WindingConvention {
+    /// Outer contours have positive signed area, holes negative
+    Positive,
+    /// Outer contours have negative signed area, holes positive
+    Negative,
+    /// Keep the orientation produced by the sweep
+    AsComputed,
+}
+
+/// Reverse result contours as needed so they follow a winding convention
+fn orient_contours(paths: &[Arc<BezierPath>], convention: WindingConvention) {
+    let outer_sign = match convention {
+        WindingConvention::Positive => 1.0,
+        WindingConvention::Negative => -1.0,
+        WindingConvention::AsComputed => return,
+    };
+    let contours: Vec<BezPath> = paths.iter().map(|p| p.to_kurbo_path()).collect();
+    let parents = subpath_parents(&contours);
+
+    for (i, (path, contour)) in paths.iter().zip(&contours).enumerate() {
+        let sign = if nesting_depth(&parents, i).is_multiple_of(2) {
+            outer_sign
+        } else {
+            -outer_sign
+        };
+        if contour.area() * sign < 0.0 {
+            *lock(&path.path) = contour.reverse_subpaths();
+        }
+    }
+}
+
+/// Perform a boolean operation and orient the resulting contours
+///
+/// With `WindingConvention::AsComputed` this is the same as
+/// `boolean_operation`; otherwise outer contours and holes are given
+/// opposite, predictable orientations regardless of the operation.
+#[uniffi::export]
+pub fn boolean_operation_winding(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+    output_winding: WindingConvention,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let result = boolean_operation(path_a, path_b, operation, fill_rule)?;
+    orient_contours(&result.paths, output_winding);
+    Ok(result)
+}
+
+/// Results of all four boolean operations on the same pair of paths
+#[derive(uniffi::Record)]
+pub struct AllBooleanResults {
+    pub union: BooleanOperationResult,
+    pub intersection: BooleanOperationResult,
+    pub difference: BooleanOperationResult,
+    pub xor: BooleanOperationResult,
+}
+
+/// Perform all four boolean operations at once
+///
+/// Each result is the one `boolean_operation` gives, open subpaths and
+/// empty or disjoint operands included. The expensive part of a boolean
+/// operation is building the arrangement of both paths; this builds it at
+/// most once and extracts every result that needs it from there, which is
+/// much cheaper than four separate `boolean_operation` calls.
+#[uniffi::export]
+pub fn boolean_all(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    fill_rule: FillRule,
+) -> Result<AllBooleanResults, LineSweeperError> {
+    let (kurbo_a, kurbo_b) = prepare_operands(path_a, path_b);
+    let mut topology = None;
+    let mut run = |operation| {
+        prepared_boolean(&kurbo_a, &kurbo_b, operation, fill_rule, &mut topology)
+    };
+
+    Ok(AllBooleanResults {
+        union: run(BooleanOperation::Union)?,
+        intersection: run(BooleanOperation::Intersection)?,
+        difference: run(BooleanOperation::Difference)?,
+        xor: run(BooleanOperation::Xor)?,
+    })
+}
+
+/// Write paths as a GeoJSON MultiPolygon geometry.
+///
+/// GeoJSON is polygon-only, so all curves are flattened to within
+/// `flatten_tolerance`. Each outer subpath of a path becomes one polygon,
+/// with the subpaths directly nested inside it as its holes. Winding is
+/// normalized to the GeoJSON right-hand rule: exterior rings run
+/// counterclockwise and holes clockwise, with y pointing up.
+#[uniffi::export]
+pub fn to_geojson(paths: Vec<Arc<BezierPath>>, flatten_tolerance: f64) -> String {
+    let mut polygons = Vec::new();
+
+    for path in &paths {
+        let rings: Vec<Vec<Point>> = flatten_subpaths(&path.to_kurbo_path(), flatten_tolerance)
+            .into_iter()
+            .map(|polyline| polyline.points)
+            .filter(|ring| ring.len() >= 3)
+            .collect();
+        let parents = ring_parents(&rings);
+
+        for (i, ring) in rings.iter().enumerate() {
+            if !nesting_depth(&parents, i).is_multiple_of(2) {
+                continue;
+            }
+            let mut polygon = vec![geojson_ring(ring, true)];
+            for (j, hole) in rings.iter().enumerate() {
+                if parents[j] == Some(i) {
+                    polygon.push(geojson_ring(hole, false));
+                }
+            }
+            polygons.push(format!("[{}]", polygon.join(",")));
+        }
+    }
+
+    format!(
+        "{{\"type\":\"MultiPolygon\",\"coordinates\":[{}]}}",
+        polygons.join(",")
+    )
+}
+
+/// Check whether the affine `[a, b, c, d, e, f]` would collapse a shape
+///
+/// Returns true when the determinant of the linear part is zero relative to
+/// the length of its columns (the shape flattens to a line or a point), or
+/// when any component is not finite.
+#[uniffi::export]
+pub fn affine_is_degenerate(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> bool {
+    let affine = Affine::new([a, b, c, d, e, f]);
+    if !affine.is_finite() {
+        return true;
+    }
+    affine.determinant().abs() <= 1e-12 * a.hypot(b) * c.hypot(d)
+}
+
+/// Get the boundary of the region where two paths disagree
+///
+/// This is the outline of the symmetric difference (XOR) of the two paths,
+/// returned as open paths — one per boundary loop, with the closing edge
+/// made explicit — rather than fillable contours. Edges shared by both
+/// paths cancel out and are not part of the boundary.
+#[uniffi::export]
+pub fn difference_boundary(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    fill_rule: FillRule,
+) -> Result<Vec<Arc<BezierPath>>, LineSweeperError> {
+    let (kurbo_a, kurbo_b) = prepare_operands(path_a, path_b);
+    let result = checked_binary_op(&kurbo_a, &kurbo_b, LsFillRule::from(fill_rule), BinaryOp::Xor)?;
+
+    // Cleaned up and restored like any boolean result, so that cancelled
+    // edges leave no slivers and untouched vertices keep their coordinates
+    let result = boolean_result(result);
+    restore_input_points(&result, &[&kurbo_a, &kurbo_b], sweep_epsilon(&kurbo_a, &kurbo_b)?);
+    Ok(result
+        .paths
+        .iter()
+        .map(|path| Arc::new(BezierPath::from_kurbo_path(open_subpaths(&path.to_kurbo_path()))))
+        .collect())
+}
+
+/// The two offsets of a path's centreline, from `BezierPath::rails`
+#[derive(uniffi::Record)]
+pub struct RailPair {
+    /// The rail to the left of the direction of travel
+    pub left: Arc<BezierPath>,
+    /// The rail to the right of the direction of travel
+    pub right: Arc<BezierPath>,
+}
+
+/// Tolerance used when computing stroke outlines, relative to the stroke width
+const STROKE_TOLERANCE: f64 = 1e-3;
+
+/// A path together with the parameters it is stroked with
+///
+/// Keeping the width next to the geometry means transforms scale both
+/// consistently.
+#[derive(uniffi::Object)]
+pub struct StrokedPath {
+    path: Arc<BezierPath>,
+    width: f64,
+    cap: LineCapKind,
+    join: LineJoinKind,
+}
+
+#[uniffi::export]
+impl StrokedPath {
+    /// Create a new StrokedPath
+    ///
+    /// Returns an `InvalidArgument` error unless `width` is positive and finite.
+    #[uniffi::constructor]
+    pub fn new(
+        path: Arc<BezierPath>,
+        width: f64,
+        cap: LineCapKind,
+        join: LineJoinKind,
+    ) -> Result<Self, LineSweeperError> {
+        check_stroke_width(width)?;
+        Ok(Self {
+            path,
+            width,
+            cap,
+            join,
+        })
+    }
+
+    /// Get the stroked path
+    pub fn path(&self) -> Arc<BezierPath> {
+        self.path.clone()
+    }
+
+    /// Get the stroke width
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Apply the affine `[a, b, c, d, e, f]`, scaling the stroke width along with the path
+    ///
+    /// The width is multiplied by the average scale factor of the affine,
+    /// the square root of the absolute value of its determinant. Returns an
+    /// `InvalidArgument` error if the affine isn't finite or collapses the
+    /// width to zero.
+    pub fn transformed(
+        &self,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) -> Result<Arc<StrokedPath>, LineSweeperError> {
+        let affine = Affine::new([a, b, c, d, e, f]);
+        if !affine.is_finite() {
+            return Err(LineSweeperError::InvalidArgument(format!(
+                "affine must be finite, got {:?}",
+                affine.as_coeffs()
+            )));
+        }
+        let width = self.width * affine.determinant().abs().sqrt();
+        check_stroke_width(width)?;
+        let mut path = self.path.to_kurbo_path();
+        path.apply_affine(affine);
+
+        Ok(Arc::new(StrokedPath {
+            path: Arc::new(BezierPath::from_kurbo_path(path)),
+            width,
+            cap: self.cap,
+            join: self.join,
+        }))
+    }
+
+    /// Get the fillable outline of the stroke at its current width
+    ///
+    /// This is `stroke_outline` on the path with the stored parameters.
+    pub fn outline(&self) -> Result<Arc<BezierPath>, LineSweeperError> {
+        self.path.stroke_outline(self.width, self.cap, self.join)
+    }
+}
+
+/// Perform a boolean operation on a fixed snapping grid
+///
+/// Every coordinate of both inputs is rounded to a multiple of `grid`
+/// before the sweep, and every output coordinate is snapped to the same
+/// grid, so inputs that are identical after snapping give bit-identical
+/// results on every platform. A grid that is too coarse can collapse thin
+/// features of the input.
+#[uniffi::export]
+pub fn boolean_operation_snapped(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+    grid: f64,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    if !(grid.is_finite() && grid > 0.0) {
+        return Err(LineSweeperError::InvalidArgument(format!(
+            "grid must be positive and finite, got {grid}"
+        )));
+    }
+
+    let snapped_a = BezierPath::from_kurbo_path(snap_to_grid(&path_a.to_kurbo_path(), grid));
+    let snapped_b = BezierPath::from_kurbo_path(snap_to_grid(&path_b.to_kurbo_path(), grid));
+    let result = boolean_operation(&snapped_a, &snapped_b, operation, fill_rule)?;
+
+    for path in &result.paths {
+        let mut path = lock(&path.path);
+        *path = snap_to_grid(&path, grid);
+    }
+    Ok(result)
+}
+
+/// Perform a boolean operation and order the contours by the input they come from
+///
+/// Each result contour is attributed to `path_a` or `path_b` depending on
+/// which input most of its boundary (by arc length) lies along. Contours
+/// from `path_a` come first, then those from `path_b`; within each group
+/// contours are sorted top to bottom, then left to right, by the corner of
+/// their bounding box. This gives a stable order that doesn't depend on the
+/// sweep.
+#[uniffi::export]
+pub fn boolean_operation_ordered(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let mut result = boolean_operation(path_a, path_b, operation, fill_rule)?;
+    order_by_source(
+        &mut result.paths,
+        &path_a.to_kurbo_path(),
+        &path_b.to_kurbo_path(),
+    );
+    Ok(result)
+}
+
+/// Distance from `p` to the nearest point on the outline of `path`
+fn distance_to_outline(path: &BezPath, p: Point) -> f64 {
+    path.segments()
+        .map(|seg| seg.nearest(p, 1e-9).distance_sq)
+        .fold(f64::INFINITY, f64::min)
+        .sqrt()
+}
+
+/// Whether most of a contour's boundary lies along `path_a` rather than `path_b`
+fn follows_first_input(contour: &BezPath, path_a: &BezPath, path_b: &BezPath) -> bool {
+    let mut balance = 0.0;
+    for seg in contour.segments() {
+        let mid = seg.eval(0.5);
+        let length = seg.arclen(1e-6);
+        if distance_to_outline(path_a, mid) <= distance_to_outline(path_b, mid) {
+            balance += length;
+        } else {
+            balance -= length;
+        }
+    }
+    balance >= 0.0
+}
+
+fn order_by_source(paths: &mut [Arc<BezierPath>], path_a: &BezPath, path_b: &BezPath) {
+    let mut keyed: Vec<(bool, Point, Arc<BezierPath>)> = paths
+        .iter()
+        .map(|path| {
+            let contour = path.to_kurbo_path();
+            let bbox = contour.bounding_box();
+            let from_a = follows_first_input(&contour, path_a, path_b);
+            (!from_a, Point::new(bbox.x0, bbox.y0), path.clone())
+        })
+        .collect();
+    keyed.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(a.1.y.total_cmp(&b.1.y))
+            .then(a.1.x.total_cmp(&b.1.x))
+    });
+    for (slot, (_, _, path)) in paths.iter_mut().zip(keyed) {
+        *slot = path;
+    }
+}
+
+/// Render a boolean operation result as an SVG `<path>` element
+///
+/// All contours go into a single `d` attribute, and `fill-rule` is set to
+/// match `fill_rule`, so the SVG renders the same region the operation
+/// computed with.
+#[uniffi::export]
+pub fn result_to_svg(result: &BooleanOperationResult, fill_rule: FillRule) -> String {
+    let mut path = BezPath::new();
+    for contour in &result.paths {
+        path.extend(contour.to_kurbo_path());
+    }
+    let rule = match fill_rule {
+        FillRule::EvenOdd => "evenodd",
+        FillRule::NonZero => "nonzero",
+    };
+    format!("<path d=\"{}\" fill-rule=\"{}\"/>", path.to_svg(), rule)
+}
+
+/// Describe each contour of a boolean operation result
+///
+/// The infos are in the same order as `result.paths`. A contour is a hole
+/// when an odd number of other contours enclose it; in a result, holes run
+/// opposite to the contours around them, so under the nonzero rule they
+/// cancel their parent's winding.
+#[uniffi::export]
+pub fn contour_infos(result: &BooleanOperationResult) -> Vec<ContourInfo> {
+    let contours = result_contours(result);
+    let parents = subpath_parents(&contours);
+    contours
+        .iter()
+        .enumerate()
+        .map(|(i, contour)| {
+            let signed_area = contour.area();
+            ContourInfo {
+                signed_area,
+                is_hole: !nesting_depth(&parents, i).is_multiple_of(2),
+                winding: if signed_area > 0.0 {
+                    1
+                } else if signed_area < 0.0 {
+                    -1
+                } else {
+                    0
+                },
+            }
+        })
+        .collect()
+}
+
+/// Count the elements of each contour of a boolean operation result
+///
+/// The counts are in the same order as `result.paths` and include the
+/// `MoveTo` and `ClosePath` of each contour, like `get_segments().len()`.
+#[uniffi::export]
+pub fn contour_element_counts(result: &BooleanOperationResult) -> Vec<u64> {
+    result
+        .paths
+        .iter()
+        .map(|path| lock(&path.path).elements().len() as u64)
+        .collect()
+}
+
+/// A boolean operation result as one path per fill rule, from `result_as_both`
+#[derive(uniffi::Record)]
+pub struct BothFillPaths {
+    /// The contours as they are, to fill under the even-odd rule
+    pub even_odd: Arc<BezierPath>,
+    /// The contours oriented so that holes cancel, to fill under nonzero
+    pub non_zero: Arc<BezierPath>,
+}
+
+/// Merge the contours of a result into a compound path for each fill rule
+///
+/// Even-odd filling leaves holes empty whichever way they run, so that
+/// path is the contours concatenated. For nonzero, outer contours are made
+/// counterclockwise and holes clockwise, as by `WindingConvention::Positive`.
+#[uniffi::export]
+pub fn result_as_both(result: &BooleanOperationResult) -> BothFillPaths {
+    let oriented: Vec<Arc<BezierPath>> = result.paths.iter().map(|path| path.duplicate()).collect();
+    orient_contours(&oriented, WindingConvention::Positive);
+    BothFillPaths {
+        even_odd: Arc::new(BezierPath::concat(result.paths.clone())),
+        non_zero: Arc::new(BezierPath::concat(oriented)),
+    }
+}
+
+/// Get the versions of the geometry libraries this build embeds
+///
+/// Versions are taken from `Cargo.lock` at build time; git dependencies
+/// include their short revision.
+#[uniffi::export]
+pub fn engine_info() -> EngineInfo {
+    EngineInfo {
+        linesweeper_version: env!("LINESWEEPER_VERSION").to_string(),
+        kurbo_version: env!("KURBO_VERSION").to_string(),
+        supports_curves: true,
+    }
+}
+
+/// A boolean operation result along with how much it changed the area of the first input
+#[derive(uniffi::Record)]
+pub struct AreaDeltaResult {
+    pub result: BooleanOperationResult,
+    /// The result's filled area minus the filled area of `path_a`
+    pub area_delta: f64,
+}
+
+/// Perform a boolean operation and measure the area it added or removed
+///
+/// The delta is negative when area was removed, as expected for a
+/// difference, and positive when it was added, as for a union. Both areas
+/// come from the same sweep, so `path_a`'s area is measured under
+/// `fill_rule` even if it overlaps itself.
+#[uniffi::export]
+pub fn boolean_operation_with_area_delta(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+    fill_rule: FillRule,
+) -> Result<AreaDeltaResult, LineSweeperError> {
+    let (kurbo_a, kurbo_b) = prepare_operands(path_a, path_b);
+    let eps = sweep_epsilon(&kurbo_a, &kurbo_b)?;
+    let topology = sweep_with_tolerance(&kurbo_a, &kurbo_b, eps)?;
+    let result = extract_operation(&topology, operation, fill_rule)?;
+    restore_input_points(&result, &[&kurbo_a, &kurbo_b], eps);
+    let input = catch_engine_panic(|| topology.contours(|w| fill_rule.is_inside(w.shape_a)))?;
+    let input = boolean_result(input);
+
+    let area_delta = result_area(&result) - result_area(&input);
+    Ok(AreaDeltaResult { result, area_delta })
+}
+
+/// Check whether two boolean operation results have the same contour nesting
+///
+/// Each result's contours are arranged into a tree by which contour
+/// encloses which. The trees match when every contour can be paired with
+/// one in the other result whose bounding box is within `epsilon` on every
+/// side and whose children match in the same way. Exact coordinates and
+/// orientation don't matter.
+#[uniffi::export]
+pub fn same_topology(
+    a: &BooleanOperationResult,
+    b: &BooleanOperationResult,
+    epsilon: f64,
+) -> bool {
+    let a = ContourTree::new(result_contours(a));
+    let b = ContourTree::new(result_contours(b));
+    a.matches(&a.roots(), &b, &b.roots(), epsilon)
+}
+
+fn result_contours(result: &BooleanOperationResult) -> Vec<BezPath> {
+    result.paths.iter().map(|p| p.to_kurbo_path()).collect()
+}
+
+/// Contours arranged by which encloses which, for structural comparison.
+struct ContourTree {
+    bboxes: Vec<kurbo::Rect>,
+    parents: Vec<Option<usize>>,
+}
+
+impl ContourTree {
+    fn new(contours: Vec<BezPath>) -> Self {
+        ContourTree {
+            bboxes: contours.iter().map(|c| c.bounding_box()).collect(),
+            parents: subpath_parents(&contours),
+        }
+    }
+
+    fn roots(&self) -> Vec<usize> {
+        self.children_of(None)
+    }
+
+    fn children_of(&self, parent: Option<usize>) -> Vec<usize> {
+        (0..self.parents.len())
+            .filter(|&i| self.parents[i] == parent)
+            .collect()
+    }
+
+    /// Whether the subtrees under `mine` can be paired one to one with those under `theirs`.
+    fn matches(&self, mine: &[usize], other: &ContourTree, theirs: &[usize], epsilon: f64) -> bool {
+        if mine.len() != theirs.len() {
+            return false;
+        }
+        let mut unmatched = theirs.to_vec();
+        mine.iter().all(|&i| {
+            let found = unmatched.iter().position(|&j| {
+                let (r, s) = (self.bboxes[i], other.bboxes[j]);
+                let close = (r.x0 - s.x0).abs() <= epsilon
+                    && (r.y0 - s.y0).abs() <= epsilon
+                    && (r.x1 - s.x1).abs() <= epsilon
+                    && (r.y1 - s.y1).abs() <= epsilon;
+                close
+                    && self.matches(
+                        &self.children_of(Some(i)),
+                        other,
+                        &other.children_of(Some(j)),
+                        epsilon,
+                    )
+            });
+            found.map(|k| unmatched.remove(k)).is_some()
+        })
+    }
+}
+
+fn check_stroke_width(width: f64) -> Result<(), LineSweeperError> {
+    if width.is_finite() && width > 0.0 {
+        Ok(())
+    } else {
+        Err(LineSweeperError::InvalidArgument(format!(
+            "stroke width must be positive and finite, got {width}"
+        )))
+    }
+}
+
+fn check_radius(radius: f64) -> Result<(), LineSweeperError> {
+    if radius.is_finite() && radius >= 0.0 {
+        Ok(())
+    } else {
+        Err(LineSweeperError::InvalidArgument(format!(
+            "radius must be non-negative and finite, got {radius}"
+        )))
+    }
+}
+
+/// Resolve a path's overlaps into the region it fills
+///
+/// Self-intersecting and overlapping subpaths are replaced by the
+/// non-overlapping contours of the region they fill under `fill_rule`,
+/// with each hole as a separate contour. This is the union of the path
+/// with an empty one.
+#[uniffi::export]
+pub fn simplify(
+    path: &BezierPath,
+    fill_rule: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    boolean_operation(path, &BezierPath::new(), BooleanOperation::Union, fill_rule)
+}
+
+/// The region a path fills under `fill_rule`, as non-overlapping contours
+fn filled_region(path: &BezierPath, fill_rule: FillRule) -> Result<BezPath, LineSweeperError> {
+    let result = simplify(path, fill_rule)?;
+    Ok(result.paths.iter().flat_map(|p| p.to_kurbo_path()).collect())
+}
+
+/// Grow a region by `distance`, or shrink it if `distance` is negative
+///
+/// `region` must not overlap itself, as returned by `filled_region`. The
+/// boundary is stroked with round joins at twice the distance, and the
+/// stroke is added to or removed from the region.
+fn offset_region(
+    region: &BezPath,
+    distance: f64,
+    accuracy: f64,
+) -> Result<BezPath, LineSweeperError> {
+    if distance == 0.0 || region.is_empty() {
+        return Ok(region.clone());
+    }
+    let style = Stroke::new(2.0 * distance.abs())
+        .with_caps(Cap::Round)
+        .with_join(Join::Round);
+    let band = kurbo::stroke(region.iter(), &style, &StrokeOpts::default(), accuracy);
+    let op = if distance > 0.0 {
+        BinaryOp::Union
+    } else {
+        BinaryOp::Difference
+    };
+    let contours = checked_binary_op(region, &band, LsFillRule::NonZero, op)?;
+    let result = boolean_result(contours);
+    restore_input_points(&result, &[region, &band], sweep_epsilon(region, &band)?);
+    Ok(result.paths.iter().flat_map(|p| p.to_kurbo_path()).collect())
+}
+
+/// Reusable context for running many boolean operations in a row
+///
+/// Each operation copies its inputs out of their `BezierPath`s, closing
+/// open subpaths, before sweeping; a context keeps the two buffers those
+/// copies are made into between calls instead of allocating new ones every
+/// time. That saves just two allocations per call, once the buffers have
+/// grown to fit the largest inputs: the sweep, its contours and the result
+/// paths are still allocated afresh by every operation.
+#[derive(uniffi::Object)]
+pub struct BooleanContext {
+    scratch: Mutex<(BezPath, BezPath)>,
+}
+
+impl Default for BooleanContext {
+    fn default() -> Self {
+        Self {
+            scratch: Mutex::new((BezPath::new(), BezPath::new())),
+        }
+    }
+}
+
+#[uniffi::export]
+impl BooleanContext {
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Perform a boolean operation
+    ///
+    /// The result is the same as from `boolean_operation`, only computed
+    /// in the context's buffers.
+    pub fn op(
+        &self,
+        path_a: &BezierPath,
+        path_b: &BezierPath,
+        operation: BooleanOperation,
+        fill_rule: FillRule,
+    ) -> Result<BooleanOperationResult, LineSweeperError> {
+        let mut scratch = lock(&self.scratch);
+        let (kurbo_a, kurbo_b) = &mut *scratch;
+        path_a.copy_closed_into(kurbo_a);
+        path_b.copy_closed_into(kurbo_b);
+        prepared_boolean(kurbo_a, kurbo_b, operation, fill_rule, &mut None)
+    }
+}
+
+/// Find where two paths cross, as segment indices and parameters on each
+///
+/// Segments are numbered in path order, with the closing edge of a closed
+/// subpath counted as a segment after its last drawn one. A crossing at a
+/// vertex shared by two segments is reported once, on the earlier segment.
+/// Paths with non-finite coordinates have no intersections.
+#[uniffi::export]
+pub fn intersection_params(path_a: &BezierPath, path_b: &BezierPath) -> Vec<IntersectionParam> {
+    let (kurbo_a, kurbo_b) = (path_a.to_kurbo_path(), path_b.to_kurbo_path());
+    if check_finite(&kurbo_a).is_err() || check_finite(&kurbo_b).is_err() {
+        return Vec::new();
+    }
+    let bbox = kurbo_a.bounding_box().union(kurbo_b.bounding_box());
+    let tolerance = bbox.width().hypot(bbox.height()) * 1e-9;
+
+    let segments_b: Vec<PathSeg> = kurbo_b.segments().collect();
+    let mut params: Vec<IntersectionParam> = Vec::new();
+    for (seg_a, a) in kurbo_a.segments().enumerate() {
+        let box_a = control_box(&a).inflate(tolerance, tolerance);
+        for (seg_b, b) in segments_b.iter().enumerate() {
+            if !box_a.overlaps(control_box(b)) {
+                continue;
+            }
+            for (t_a, t_b) in segment_intersections(&a, b, tolerance) {
+                let point = a.eval(t_a);
+                if params
+                    .iter()
+                    .any(|other| Point::from(other.point).distance(point) <= tolerance * 16.0)
+                {
+                    continue;
+                }
+                params.push(IntersectionParam {
+                    point: point.into(),
+                    seg_a: seg_a as u64,
+                    t_a,
+                    seg_b: seg_b as u64,
+                    t_b,
+                });
+            }
+        }
+    }
+    params
+}
+
+/// Find the points where two outlines cross or touch
+///
+/// These are the points of `intersection_params` without the segment
+/// data. Where lines of the two paths overlap along a stretch, the ends of
+/// the overlap are reported too. Paths with non-finite coordinates have no
+/// intersections.
+#[uniffi::export]
+pub fn intersections(path_a: &BezierPath, path_b: &BezierPath) -> Vec<Point2D> {
+    let (kurbo_a, kurbo_b) = (path_a.to_kurbo_path(), path_b.to_kurbo_path());
+    if check_finite(&kurbo_a).is_err() || check_finite(&kurbo_b).is_err() {
+        return Vec::new();
+    }
+    let bbox = kurbo_a.bounding_box().union(kurbo_b.bounding_box());
+    let tolerance = bbox.width().hypot(bbox.height()) * 1e-9;
+
+    let mut points: Vec<Point> = intersection_params(path_a, path_b)
+        .into_iter()
+        .map(|param| Point::from(param.point))
+        .collect();
+    let lines = |path: &BezPath| -> Vec<kurbo::Line> {
+        let lines = path.segments().filter_map(|seg| match seg {
+            PathSeg::Line(line) => Some(line),
+            _ => None,
+        });
+        lines.collect()
+    };
+    let lines_b = lines(&kurbo_b);
+    for a in lines(&kurbo_a) {
+        for b in &lines_b {
+            let Some((lo, hi)) = collinear_overlap(a, *b, tolerance) else {
+                continue;
+            };
+            for end in [a.eval(lo), a.eval(hi)] {
+                if points.iter().all(|p| p.distance(end) > tolerance * 16.0) {
+                    points.push(end);
+                }
+            }
+        }
+    }
+    points.into_iter().map(Point2D::from).collect()
+}
+
+/// The span of `a`, as parameters on it, that lies along a collinear `b`.
+///
+/// Returns `None` unless both ends of `b` are within `tolerance` of the
+/// line through `a` and the two overlap.
+fn collinear_overlap(a: kurbo::Line, b: kurbo::Line, tolerance: f64) -> Option<(f64, f64)> {
+    let direction = a.p1 - a.p0;
+    let length = direction.hypot();
+    let offset = |p: Point| direction.cross(p - a.p0).abs() / length;
+    if length <= tolerance || offset(b.p0) > tolerance || offset(b.p1) > tolerance {
+        return None;
+    }
+    let along = |p: Point| direction.dot(p - a.p0) / (length * length);
+    let (t0, t1) = (along(b.p0), along(b.p1));
+    let (lo, hi) = (t0.min(t1).max(0.0), t0.max(t1).min(1.0));
+    (lo <= hi).then_some((lo, hi))
+}
+
+/// Find the parts of two outlines that lie on top of each other
+///
+/// Lines of the two paths that are collinear to within `tolerance` share
+/// the span where they overlap, and curves are shared when they match to
+/// within `tolerance`, in either direction. Closing edges count. The
+/// shared portions are returned as open paths running along `path_a`, with
+/// consecutive portions joined. Shapes that only touch at points share
+/// nothing.
+#[uniffi::export]
+pub fn shared_edges(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    tolerance: f64,
+) -> Vec<Arc<BezierPath>> {
+    let segments_b: Vec<PathSeg> = path_b.to_kurbo_path().segments().collect();
+    let mut shared: Vec<BezPath> = Vec::new();
+    let mut push_span = |span: PathSeg| {
+        let joined = shared.last_mut().filter(|last| {
+            let end = last.elements().last().and_then(|el| el.end_point());
+            end.is_some_and(|end| end.distance(span.start()) <= tolerance)
+        });
+        match joined {
+            Some(last) => last.push(span.as_path_el()),
+            None => {
+                let mut path = BezPath::new();
+                path.move_to(span.start());
+                path.push(span.as_path_el());
+                shared.push(path);
+            }
+        }
+    };
+
+    for a in path_a.to_kurbo_path().segments() {
+        let box_a = control_box(&a).inflate(tolerance, tolerance);
+        let mut spans: Vec<(f64, f64)> = Vec::new();
+        for b in segments_b.iter().filter(|b| box_a.overlaps(control_box(b))) {
+            match (a, *b) {
+                (PathSeg::Line(la), PathSeg::Line(lb)) => {
+                    let length = (la.p1 - la.p0).hypot();
+                    if let Some((lo, hi)) = collinear_overlap(la, lb, tolerance) {
+                        if (hi - lo) * length > tolerance {
+                            spans.push((lo, hi));
+                        }
+                    }
+                }
+                (PathSeg::Line(_), _) | (_, PathSeg::Line(_)) => {}
+                (a, b) => {
+                    let matches = |b: &PathSeg| segs_approx_eq(&a, b, tolerance);
+                    if matches(&b) || matches(&b.reverse()) {
+                        spans.push((0.0, 1.0));
+                    }
+                }
+            }
+        }
+
+        // Merge overlapping spans along this segment, in order
+        spans.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut merged: Vec<(f64, f64)> = Vec::new();
+        for (lo, hi) in spans {
+            match merged.last_mut() {
+                Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        for (lo, hi) in merged {
+            push_span(a.subsegment(lo..hi));
+        }
+    }
+    shared
+        .into_iter()
+        .map(|path| Arc::new(BezierPath::from_kurbo_path(path)))
+        .collect()
+}
+
+/// Union any number of paths in one call
+///
+/// Each path is filled under `fill_rule` on its own, and the union of all
+/// of them is returned, as from `boolean_operation`. The paths are merged
+/// one at a time into the accumulated result. An empty list gives an empty
+/// result.
+#[uniffi::export]
+pub fn union_all(
+    paths: Vec<Arc<BezierPath>>,
+    fill_rule: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let inputs: Vec<BezPath> = paths.iter().map(|p| close_subpaths(&p.to_kurbo_path())).collect();
+    let mut accumulated = BezPath::new();
+    let mut contours = None;
+    let mut eps = 0.0_f64;
+    for closed in &inputs {
+        eps = eps.max(sweep_epsilon(&accumulated, closed)?);
+        let merged = checked_binary_op(
+            &accumulated,
+            closed,
+            LsFillRule::from(fill_rule),
+            BinaryOp::Union,
+        )?;
+        accumulated = merged
+            .contours()
+            .filter(|contour| !is_sliver(&contour.path))
+            .flat_map(|contour| contour.path.iter())
+            .collect();
+        contours = Some(merged);
+    }
+    let result = match contours {
+        Some(contours) => boolean_result(contours),
+        None => BooleanOperationResult { paths: Vec::new() },
+    };
+    let inputs: Vec<&BezPath> = inputs.iter().collect();
+    restore_input_points(&result, &inputs, eps);
+    Ok(result)
+}
+
+/// One step of `boolean_chain`: an operation and the path it applies
+#[derive(uniffi::Record)]
+pub struct BooleanStep {
+    /// The right-hand operand of the operation
+    pub operand: Arc<BezierPath>,
+    /// The operation combining the result so far with `operand`
+    pub operation: BooleanOperation,
+}
+
+/// Apply a sequence of boolean operations in one call
+///
+/// Each step combines the result of the previous ones, starting from
+/// `start`, with its operand, so `((A ∪ B) − C) ∩ D` is `A` with three
+/// steps. Every operand is filled under `fill_rule`. No steps gives the
+/// region `start` fills, as from `simplify`.
+#[uniffi::export]
+pub fn boolean_chain(
+    start: &BezierPath,
+    steps: Vec<BooleanStep>,
+    fill_rule: FillRule,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let Some((first, rest)) = steps.split_first() else {
+        return simplify(start, fill_rule);
+    };
+    let mut result = boolean_operation(start, &first.operand, first.operation, fill_rule)?;
+    for step in rest {
+        let accumulated = BezierPath::concat(result.paths);
+        result = boolean_operation(&accumulated, &step.operand, step.operation, fill_rule)?;
+    }
+    Ok(result)
+}
+
+/// Perform a boolean operation on shapes made only of horizontal and vertical lines
+///
+/// The result is computed on the grid of the input coordinates, without
+/// any floating-point intersection, so every output vertex has exactly the
+/// coordinates of input vertices. Each path is filled under its default
+/// fill rule (see `BezierPath::set_default_fill_rule`). Outer contours run
+/// counterclockwise and holes clockwise, and contours that touch only at a
+/// corner are kept separate. Returns an error if either path has a curve or
+/// a diagonal line, including the closing edge that `boolean_operation`
+/// adds to an open subpath.
+#[uniffi::export]
+pub fn boolean_operation_rectilinear(
+    path_a: &BezierPath,
+    path_b: &BezierPath,
+    operation: BooleanOperation,
+) -> Result<BooleanOperationResult, LineSweeperError> {
+    let kurbo_a = close_subpaths(&path_a.to_kurbo_path());
+    let kurbo_b = close_subpaths(&path_b.to_kurbo_path());
+    check_finite(&kurbo_a)?;
+    check_finite(&kurbo_b)?;
+
+    let operands = [
+        (rectilinear_edges(&kurbo_a)?, path_a.default_fill_rule()),
+        (rectilinear_edges(&kurbo_b)?, path_b.default_fill_rule()),
+    ];
+    let paths = rectilinear_boolean(&operands, operation)
+        .into_iter()
+        .map(|contour| Arc::new(BezierPath::from_kurbo_path(contour)))
+        .collect();
+    Ok(BooleanOperationResult { paths })
+}
+
+/// The edges of a path, checking that each is horizontal or vertical.
+fn rectilinear_edges(path: &BezPath) -> Result<Vec<(Point, Point)>, LineSweeperError> {
+    // Adding zero turns -0 into 0, so that equal coordinates sort together
+    let normalize = |p: Point| Point::new(p.x + 0.0, p.y + 0.0);
+    path.segments()
+        .map(|seg| match seg {
+            PathSeg::Line(line) if line.p0.x == line.p1.x || line.p0.y == line.p1.y => {
+                Ok((normalize(line.p0), normalize(line.p1)))
+            }
+            PathSeg::Line(line) => Err(LineSweeperError::InvalidArgument(format!(
+                "line from ({}, {}) to ({}, {}) is not horizontal or vertical",
+                line.p0.x, line.p0.y, line.p1.x, line.p1.y
+            ))),
+            _ => Err(LineSweeperError::InvalidArgument(
+                "rectilinear operations don't support curves".to_string(),
+            )),
+        })
+        .collect()
+}
+
+/// Combine two rectilinear shapes on the grid of their coordinates
+///
+/// Each cell of the grid is classified by the winding numbers of the
+/// operands at its center, and the outline of the selected cells is traced
+/// with the filled side on the left.
+fn rectilinear_boolean(
+    operands: &[(Vec<(Point, Point)>, FillRule); 2],
+    operation: BooleanOperation,
+) -> Vec<BezPath> {
+    let grid_lines = |coord: fn(&Point) -> f64| {
+        let mut values: Vec<f64> = operands
+            .iter()
+            .flat_map(|(edges, _)| edges.iter().flat_map(|(p, q)| [coord(p), coord(q)]))
+            .collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        values
+    };
+    let (xs, ys) = (grid_lines(|p| p.x), grid_lines(|p| p.y));
+    if xs.len() < 2 || ys.len() < 2 {
+        return Vec::new();
+    }
+    let index = |values: &[f64], v: f64| values.binary_search_by(|x| x.total_cmp(&v)).unwrap();
+    let (nx, ny) = (xs.len() - 1, ys.len() - 1);
+
+    // A ray from a cell's center towards +x crosses the vertical edges to
+    // its right, upward ones adding one to the winding number
+    let mut inside = [vec![false; nx * ny], vec![false; nx * ny]];
+    for ((edges, fill_rule), inside) in operands.iter().zip(&mut inside) {
+        let mut crossings = vec![0i32; (nx + 1) * ny];
+        for &(p, q) in edges {
+            if p.x != q.x || p.y == q.y {
+                continue;
+            }
+            let (low, high, sign) = if p.y < q.y { (p.y, q.y, 1) } else { (q.y, p.y, -1) };
+            let i = index(&xs, p.x);
+            for j in index(&ys, low)..index(&ys, high) {
+                crossings[j * (nx + 1) + i] += sign;
+            }
+        }
+        for j in 0..ny {
+            let mut winding = 0;
+            for i in (0..nx).rev() {
+                winding += crossings[j * (nx + 1) + i + 1];
+                inside[j * nx + i] = fill_rule.is_inside(winding);
+            }
+        }
+    }
+    let filled = |i: usize, j: usize| {
+        i < nx && j < ny && operation.combine(inside[0][j * nx + i], inside[1][j * nx + i])
+    };
+
+    // Boundary edges between grid vertices, as start vertex and direction
+    let mut edges: Vec<((usize, usize), (isize, isize))> = Vec::new();
+    for j in 0..=ny {
+        for i in 0..nx {
+            let (below, above) = (j > 0 && filled(i, j - 1), filled(i, j));
+            if above && !below {
+                edges.push(((i, j), (1, 0)));
+            } else if below && !above {
+                edges.push(((i + 1, j), (-1, 0)));
+            }
+        }
+    }
+    for j in 0..ny {
+        for i in 0..=nx {
+            let (left, right) = (i > 0 && filled(i - 1, j), filled(i, j));
+            if left && !right {
+                edges.push(((i, j), (0, 1)));
+            } else if right && !left {
+                edges.push(((i, j + 1), (0, -1)));
+            }
+        }
+    }
+
+    let vertex = |(i, j): (usize, usize)| j * (nx + 1) + i;
+    let mut outgoing = vec![Vec::new(); (nx + 1) * (ny + 1)];
+    for (e, &(start, _)) in edges.iter().enumerate() {
+        outgoing[vertex(start)].push(e);
+    }
+    let end = |e: usize| {
+        let ((i, j), (dx, dy)) = edges[e];
+        (i.wrapping_add_signed(dx), j.wrapping_add_signed(dy))
+    };
+
+    let mut used = vec![false; edges.len()];
+    let mut contours = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let mut corners = Vec::new();
+        let mut e = first;
+        loop {
+            used[e] = true;
+            let (dx, dy) = edges[e].1;
+            // Where two contours touch at a corner, turning left keeps them apart
+            let candidates = &outgoing[vertex(end(e))];
+            let next = candidates
+                .iter()
+                .copied()
+                .find(|&n| edges[n].1 == (-dy, dx))
+                .unwrap_or(candidates[0]);
+            if edges[next].1 != (dx, dy) {
+                corners.push(end(e));
+            }
+            e = next;
+            if e == first {
+                break;
+            }
+        }
+
+        let mut contour = BezPath::new();
+        for (k, &(i, j)) in corners.iter().enumerate() {
+            let p = Point::new(xs[i], ys[j]);
+            if k == 0 {
+                contour.move_to(p);
+            } else {
+                contour.line_to(p);
+            }
+        }
+        contour.close_path();
+        contours.push(contour);
+    }
+    contours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect_path(x0: f64, y0: f64, x1: f64, y1: f64) -> BezierPath {
+        let path = BezierPath::new();
+        path.move_to(x0, y0);
+        path.line_to(x1, y0);
+        path.line_to(x1, y1);
+        path.line_to(x0, y1);
+        path.close_path();
+        path
+    }
+
+    #[test]
+    fn test_basic_path_building() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(0.0, 1.0);
+        path.close_path();
+
+        // Verify the kurbo path is not empty
+        let kurbo_path = path.to_kurbo_path();
+        assert!(!kurbo_path.is_empty());
+    }
+
+    #[test]
+    fn test_curve_path() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.curve_to(0.0, 1.0, 1.0, 1.0, 1.0, 0.0);
+        path.close_path();
+
+        let kurbo_path = path.to_kurbo_path();
+        assert!(!kurbo_path.is_empty());
+    }
+
+    #[test]
+    fn test_quad_path() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(0.5, 1.0, 1.0, 0.0);
+        path.close_path();
+
+        let kurbo_path = path.to_kurbo_path();
+        assert!(!kurbo_path.is_empty());
+    }
+
+    #[test]
+    fn test_basic_operation() {
+        // Create a simple square using the new API
+        let square = BezierPath::new();
+        square.move_to(0.0, 0.0);
+        square.line_to(1.0, 0.0);
+        square.line_to(1.0, 1.0);
+        square.line_to(0.0, 1.0);
+        square.close_path();
+
+        // Create another square offset by 0.5
+        let offset_square = BezierPath::new();
+        offset_square.move_to(0.5, 0.5);
+        offset_square.line_to(1.5, 0.5);
+        offset_square.line_to(1.5, 1.5);
+        offset_square.line_to(0.5, 1.5);
+        offset_square.close_path();
+
+        // Test union operation
+        let result = boolean_operation(
+            &square,
+            &offset_square,
+            BooleanOperation::Union,
+            FillRule::EvenOdd,
+        );
+
+        assert!(result.is_ok());
+        let paths = result.unwrap();
+        assert!(!paths.paths.is_empty());
+    }
+
+    #[test]
+    fn test_segment_extraction() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.line_to(1.0, 1.0);
+        path.close_path();
+
+        let segments = path.get_segments();
+        assert_eq!(segments.len(), 4);
+
+        match &segments[0] {
+            PathSegment::MoveTo { x, y } => {
+                assert_eq!(*x, 0.0);
+                assert_eq!(*y, 0.0);
+            }
+            _ => panic!("Expected MoveTo segment"),
+        }
+
+        match &segments[1] {
+            PathSegment::LineTo { x, y } => {
+                assert_eq!(*x, 1.0);
+                assert_eq!(*y, 0.0);
+            }
+            _ => panic!("Expected LineTo segment"),
+        }
+
+        match &segments[2] {
+            PathSegment::LineTo { x, y } => {
+                assert_eq!(*x, 1.0);
+                assert_eq!(*y, 1.0);
+            }
+            _ => panic!("Expected LineTo segment"),
+        }
+
+        match &segments[3] {
+            PathSegment::ClosePath => {}
+            _ => panic!("Expected ClosePath segment"),
+        }
+    }
+
+    #[test]
+    fn test_curve_segment_extraction() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.curve_to(0.0, 1.0, 1.0, 1.0, 1.0, 0.0);
+        path.close_path();
+
+        let segments = path.get_segments();
+        assert_eq!(segments.len(), 3);
+
+        match &segments[1] {
+            PathSegment::CurveTo { cp1_x, cp1_y, cp2_x, cp2_y, x, y } => {
+                assert_eq!(*cp1_x, 0.0);
+                assert_eq!(*cp1_y, 1.0);
+                assert_eq!(*cp2_x, 1.0);
+                assert_eq!(*cp2_y, 1.0);
+                assert_eq!(*x, 1.0);
+                assert_eq!(*y, 0.0);
+            }
+            _ => panic!("Expected CurveTo segment"),
+        }
+    }
+
+    #[test]
+    fn test_quad_segment_extraction() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(0.5, 1.0, 1.0, 0.0);
+        path.close_path();
+
+        let segments = path.get_segments();
+        assert_eq!(segments.len(), 3);
+
+        match &segments[1] {
+            PathSegment::QuadTo { cp_x, cp_y, x, y } => {
+                assert_eq!(*cp_x, 0.5);
+                assert_eq!(*cp_y, 1.0);
+                assert_eq!(*x, 1.0);
+                assert_eq!(*y, 0.0);
+            }
+            _ => panic!("Expected QuadTo segment"),
+        }
+    }
+    #[test]
+    fn test_to_geojson() {
+        let outer = BezierPath::new();
+        outer.move_to(0.0, 0.0);
+        outer.line_to(4.0, 0.0);
+        outer.line_to(4.0, 4.0);
+        outer.line_to(0.0, 4.0);
+        outer.close_path();
+        // Same orientation as the outer ring, so it must be flipped
+        outer.move_to(1.0, 1.0);
+        outer.line_to(3.0, 1.0);
+        outer.line_to(3.0, 3.0);
+        outer.line_to(1.0, 3.0);
+        outer.close_path();
+
+        let geojson = to_geojson(vec![Arc::new(outer)], 0.1);
+        assert_eq!(
+            geojson,
+            "{\"type\":\"MultiPolygon\",\"coordinates\":[[\
+             [[0,0],[4,0],[4,4],[0,4],[0,0]],\
+             [[1,3],[3,3],[3,1],[1,1],[1,3]]]]}"
+        );
+    }
+    #[test]
+    fn test_affine_is_degenerate() {
+        assert!(!affine_is_degenerate(1.0, 0.0, 0.0, 1.0, 10.0, 10.0));
+        assert!(!affine_is_degenerate(1e-6, 0.0, 0.0, 1e-6, 0.0, 0.0));
+        assert!(affine_is_degenerate(1.0, 2.0, 2.0, 4.0, 0.0, 0.0));
+        assert!(affine_is_degenerate(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+        assert!(affine_is_degenerate(f64::NAN, 0.0, 0.0, 1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_boolean_all_matches_individual_operations() {
+        // An open triangle, which both functions close before the sweep
+        let open = BezierPath::new();
+        open.move_to(0.0, 0.0);
+        open.line_to(3.0, 0.0);
+        open.line_to(0.0, 3.0);
+        let cases = [
+            (rect_path(0.0, 0.0, 2.0, 2.0), rect_path(1.0, 1.0, 3.0, 3.0)),
+            (open, rect_path(1.0, 1.0, 3.0, 3.0)),
+            (rect_path(0.0, 0.0, 1.0, 1.0), rect_path(5.0, 5.0, 6.0, 7.0)),
+        ];
+
+        for (a, b) in &cases {
+            let all = boolean_all(a, b, FillRule::NonZero).unwrap();
+            for (result, op) in [
+                (&all.union, BooleanOperation::Union),
+                (&all.intersection, BooleanOperation::Intersection),
+                (&all.difference, BooleanOperation::Difference),
+                (&all.xor, BooleanOperation::Xor),
+            ] {
+                let expected = boolean_operation(a, b, op, FillRule::NonZero).unwrap();
+                assert!(same_topology(result, &expected, 1e-9));
+                assert!((result_area(result) - result_area(&expected)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_boolean_operation_winding() {
+        let outer = rect_path(0.0, 0.0, 4.0, 4.0);
+        let inner = rect_path(1.0, 1.0, 3.0, 3.0);
+
+        for (convention, sign) in [
+            (WindingConvention::Positive, 1.0),
+            (WindingConvention::Negative, -1.0),
+        ] {
+            let result = boolean_operation_winding(
+                &outer,
+                &inner,
+                BooleanOperation::Difference,
+                FillRule::NonZero,
+                convention,
+            )
+            .unwrap();
+            assert_eq!(result.paths.len(), 2);
+
+            let mut areas: Vec<f64> = result
+                .paths
+                .iter()
+                .map(|p| p.to_kurbo_path().area() * sign)
+                .collect();
+            areas.sort_by(f64::total_cmp);
+            assert!((areas[0] + 4.0).abs() < 1e-9);
+            assert!((areas[1] - 16.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_segment_length_stats() {
+        let path = rect_path(0.0, 0.0, 3.0, 1.0);
+        let stats = path.segment_length_stats(1e-9);
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 3.0);
+        assert_eq!(stats.total, 8.0);
+        assert_eq!(stats.mean, 2.0);
+
+        let empty = BezierPath::new().segment_length_stats(1e-9);
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.min, 0.0);
+    }
+
+    #[test]
+    fn test_simplify_to_budget() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        for i in 1..=100 {
+            let x = i as f64 / 10.0;
+            path.line_to(x, (x * 3.0).sin() * 0.01);
+        }
+        path.line_to(10.0, 5.0);
+        path.close_path();
+        path.move_to(20.0, 0.0);
+        path.line_to(21.0, 0.0);
+        path.line_to(21.0, 1.0);
+        path.close_path();
+
+        let simplified = path.simplify_to_budget(10);
+        let segments = simplified.get_segments();
+        let vertices = segments
+            .iter()
+            .filter(|s| !matches!(s, PathSegment::ClosePath))
+            .count();
+        let subpaths = segments
+            .iter()
+            .filter(|s| matches!(s, PathSegment::MoveTo { .. }))
+            .count();
+        assert!(vertices <= 10);
+        assert_eq!(subpaths, 2);
+    }
+
+    #[test]
+    fn test_close_near_subpaths() {
+        let path = BezierPath::new();
+        // Nearly closed
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(0.0, 1e-4);
+        // Intentionally open
+        path.move_to(5.0, 0.0);
+        path.line_to(6.0, 0.0);
+        path.line_to(6.0, 1.0);
+
+        let closed = path.close_near_subpaths(1e-3);
+        let segments = closed.get_segments();
+        assert_eq!(segments.len(), 8);
+        match &segments[3] {
+            PathSegment::LineTo { x, y } => assert_eq!((*x, *y), (0.0, 0.0)),
+            _ => panic!("Expected LineTo segment"),
+        }
+        assert!(matches!(segments[4], PathSegment::ClosePath));
+        assert!(!matches!(segments[7], PathSegment::ClosePath));
+    }
+
+    #[test]
+    fn test_inflection_points() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        // An S-curve, symmetric about its midpoint
+        path.curve_to(1.0, 1.0, 2.0, -1.0, 3.0, 0.0);
+        path.quad_to(4.0, 1.0, 5.0, 0.0);
+
+        let points = path.inflection_points();
+        assert_eq!(points.len(), 1);
+        assert!((points[0].x - 1.5).abs() < 1e-9);
+        assert!(points[0].y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cusp_points() {
+        let path = BezierPath::new();
+        // Crossed control points give a cusp at t = 0.5
+        path.move_to(0.0, 0.0);
+        path.curve_to(2.0, 2.0, 0.0, 2.0, 2.0, 0.0);
+        // A smooth arch has none
+        path.curve_to(2.0, 1.0, 3.0, 1.0, 3.0, 0.0);
+
+        let points = path.cusp_points();
+        assert_eq!(points.len(), 1);
+        assert!((points[0].x - 1.0).abs() < 1e-9);
+        assert!((points[0].y - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_open_subpaths() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        let open = open_subpaths(&path);
+        assert_eq!(open.elements().len(), 5);
+        assert_eq!(open.elements()[4], PathEl::LineTo(Point::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_difference_boundary() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1.0, 0.0, 3.0, 2.0);
+
+        let boundary = difference_boundary(&a, &b, FillRule::NonZero).unwrap();
+        assert!(!boundary.is_empty());
+        for path in &boundary {
+            let segments = path.get_segments();
+            assert!(!segments.iter().any(|s| matches!(s, PathSegment::ClosePath)));
+        }
+
+        // Identical paths agree everywhere, so no boundary is left along their edges
+        assert!(difference_boundary(&a, &a, FillRule::NonZero).unwrap().is_empty());
+
+        // Corners away from any crossing keep their exact input coordinates
+        let a = rect_path(0.1, 0.3, 0.7, 0.9);
+        let b = rect_path(0.5, 0.1, 1.3, 0.6);
+        let boundary = difference_boundary(&a, &b, FillRule::NonZero).unwrap();
+        let boundary_bits: Vec<(u64, u64)> = boundary
+            .iter()
+            .flat_map(|path| path.to_kurbo_path().elements().to_vec())
+            .filter_map(|el| el.end_point())
+            .map(|p| (p.x.to_bits(), p.y.to_bits()))
+            .collect();
+        let untouched = [(0.1, 0.3), (0.1, 0.9), (0.7, 0.9), (0.5, 0.1), (1.3, 0.1), (1.3, 0.6)];
+        for (x, y) in untouched {
+            assert!(boundary_bits.contains(&(f64::to_bits(x), f64::to_bits(y))));
+        }
+    }
+
+    #[test]
+    fn test_stroked_path_transform_scales_width() {
+        let line = BezierPath::new();
+        line.move_to(0.0, 0.0);
+        line.line_to(10.0, 0.0);
+        let line = Arc::new(line);
+        let (cap, join) = (LineCapKind::Butt, LineJoinKind::Miter);
+        let stroked = StrokedPath::new(line.clone(), 2.0, cap, join).unwrap();
+
+        let scaled = stroked.transformed(3.0, 0.0, 0.0, 3.0, 5.0, 0.0).unwrap();
+        assert!((scaled.width() - 6.0).abs() < 1e-12);
+        match &scaled.path().get_segments()[1] {
+            PathSegment::LineTo { x, y } => assert_eq!((*x, *y), (35.0, 0.0)),
+            _ => panic!("Expected LineTo segment"),
+        }
+        // The original is left untouched
+        assert_eq!(stroked.width(), 2.0);
+
+        for width in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let result = StrokedPath::new(line.clone(), width, cap, join);
+            assert!(matches!(result, Err(LineSweeperError::InvalidArgument(_))));
+        }
+        let collapsed = stroked.transformed(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        assert!(matches!(collapsed, Err(LineSweeperError::InvalidArgument(_))));
+        let infinite = stroked.transformed(1.0, 0.0, 0.0, 1.0, f64::INFINITY, 0.0);
+        assert!(matches!(infinite, Err(LineSweeperError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_find_duplicate_subpaths() {
+        let mut kurbo_path = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        kurbo_path.extend(rect_path(2.0, 0.0, 3.0, 1.0).to_kurbo_path());
+        // Same square as the first, starting elsewhere and running backwards
+        kurbo_path.move_to((1.0, 1.0));
+        kurbo_path.line_to((1.0, 0.0));
+        kurbo_path.line_to((0.0, 0.0));
+        kurbo_path.line_to((0.0, 1.0));
+        kurbo_path.close_path();
+
+        let path = BezierPath::from_kurbo_path(kurbo_path);
+        assert_eq!(path.find_duplicate_subpaths(1e-9), vec![2]);
+    }
+
+    #[test]
+    fn test_dedup_subpaths() {
+        let mut kurbo_path = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        kurbo_path.extend(rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path().reverse_subpaths());
+        kurbo_path.extend(rect_path(2.0, 0.0, 3.0, 1.0).to_kurbo_path());
+
+        let path = BezierPath::from_kurbo_path(kurbo_path);
+        let deduped = path.dedup_subpaths(1e-9).to_kurbo_path();
+        let mut expected = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        expected.extend(rect_path(2.0, 0.0, 3.0, 1.0).to_kurbo_path());
+        assert_eq!(deduped, expected);
+    }
+
+    #[test]
+    fn test_subpath_perimeters() {
+        let mut kurbo_path = rect_path(0.0, 0.0, 2.0, 1.0).to_kurbo_path();
+        // An open subpath has no closing edge
+        kurbo_path.move_to((5.0, 0.0));
+        kurbo_path.line_to((8.0, 0.0));
+        kurbo_path.line_to((8.0, 4.0));
+
+        let path = BezierPath::from_kurbo_path(kurbo_path);
+        assert_eq!(path.subpath_perimeters(1e-9), vec![6.0, 7.0]);
+    }
+
+    #[test]
+    fn test_snap_to_grid() {
+        let path = BezierPath::new();
+        path.move_to(0.26, -0.74);
+        path.quad_to(1.1, 0.9, 2.49, 0.0);
+        let snapped = snap_to_grid(&path.to_kurbo_path(), 0.5);
+        assert_eq!(
+            snapped.elements(),
+            &[
+                PathEl::MoveTo(Point::new(0.5, -0.5)),
+                PathEl::QuadTo(Point::new(1.0, 1.0), Point::new(2.5, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_boolean_operation_snapped() {
+        let a = rect_path(0.01, 0.02, 2.01, 1.98);
+        let b = rect_path(1.0, 1.0, 3.0, 3.0);
+        let result =
+            boolean_operation_snapped(&a, &b, BooleanOperation::Union, FillRule::NonZero, 0.25)
+                .unwrap();
+        for path in &result.paths {
+            for el in path.to_kurbo_path().elements() {
+                if let Some(p) = el.end_point() {
+                    assert_eq!((p.x * 4.0).fract(), 0.0);
+                    assert_eq!((p.y * 4.0).fract(), 0.0);
+                }
+            }
+        }
+
+        assert!(boolean_operation_snapped(&a, &b, BooleanOperation::Union, FillRule::NonZero, 0.0)
+            .is_err());
+    }
+
+    struct Translate(f64);
+
+    impl SegmentMapper for Translate {
+        fn map(&self, segment: PathSegment) -> PathSegment {
+            let el = PathEl::from(segment);
+            PathSegment::from(map_points(el, |p| Point::new(p.x + self.0, p.y)))
+        }
+    }
+
+    struct ToLine;
+
+    impl SegmentMapper for ToLine {
+        fn map(&self, segment: PathSegment) -> PathSegment {
+            match segment {
+                PathSegment::MoveTo { x, y } => PathSegment::LineTo { x, y },
+                other => other,
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_segments() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
+        let moved = path.map_segments(Box::new(Translate(2.0))).unwrap();
+        assert_eq!(
+            moved.to_kurbo_path(),
+            rect_path(2.0, 0.0, 3.0, 1.0).to_kurbo_path()
+        );
+
+        assert!(matches!(
+            path.map_segments(Box::new(ToLine)),
+            Err(LineSweeperError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_convex() {
+        assert!(rect_path(0.0, 0.0, 2.0, 1.0).is_convex(1e-6));
+
+        let notch = BezierPath::new();
+        notch.move_to(0.0, 0.0);
+        notch.line_to(2.0, 0.0);
+        notch.line_to(2.0, 2.0);
+        notch.line_to(1.0, 1.0);
+        notch.line_to(0.0, 2.0);
+        notch.close_path();
+        assert!(!notch.is_convex(1e-6));
+
+        // A slight dent is ignored when it is within tolerance
+        let dented = BezierPath::new();
+        dented.move_to(0.0, 0.0);
+        dented.line_to(1.0, 0.01);
+        dented.line_to(2.0, 0.0);
+        dented.line_to(2.0, 2.0);
+        dented.line_to(0.0, 2.0);
+        dented.close_path();
+        assert!(!dented.is_convex(1e-3));
+        assert!(dented.is_convex(0.1));
+
+        let star = BezierPath::new();
+        for i in 0..5 {
+            let angle = (i * 2) as f64 * std::f64::consts::TAU / 5.0;
+            if i == 0 {
+                star.move_to(angle.cos(), angle.sin());
+            } else {
+                star.line_to(angle.cos(), angle.sin());
+            }
+        }
+        star.close_path();
+        assert!(!star.is_convex(1e-6));
+
+        let two = rect_path(0.0, 0.0, 1.0, 1.0);
+        two.move_to(3.0, 0.0);
+        two.line_to(4.0, 0.0);
+        two.line_to(4.0, 1.0);
+        two.close_path();
+        assert!(!two.is_convex(1e-6));
+    }
+
+    #[test]
+    fn test_order_by_source() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0).to_kurbo_path();
+        let b = rect_path(10.0, 0.0, 12.0, 2.0).to_kurbo_path();
+        let mut a_low = a.clone();
+        a_low.apply_affine(Affine::translate((0.0, 5.0)));
+
+        let mut paths: Vec<Arc<BezierPath>> = [b.clone(), a_low.clone(), a.clone()]
+            .into_iter()
+            .map(|p| Arc::new(BezierPath::from_kurbo_path(p)))
+            .collect();
+        order_by_source(&mut paths, &a, &b);
+
+        let ordered: Vec<BezPath> = paths.iter().map(|p| p.to_kurbo_path()).collect();
+        assert_eq!(ordered, vec![a.clone(), a_low, b]);
+    }
+
+    #[test]
+    fn test_contains_points() {
+        let path = rect_path(0.0, 0.0, 4.0, 4.0);
+        path.move_to(1.0, 1.0);
+        path.line_to(3.0, 1.0);
+        path.line_to(3.0, 3.0);
+        path.line_to(1.0, 3.0);
+        path.close_path();
+
+        let points = vec![
+            Point2D { x: 0.5, y: 0.5 },
+            Point2D { x: 2.0, y: 2.0 },
+            Point2D { x: 5.0, y: 2.0 },
+        ];
+        assert_eq!(
+            path.contains_points(points.clone(), FillRule::EvenOdd),
+            vec![true, false, false]
+        );
+        assert_eq!(
+            path.contains_points(points, FillRule::NonZero),
+            vec![true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_start_and_end_tangent() {
+        let path = BezierPath::new();
+        assert_eq!(path.start_tangent(), None);
+        path.move_to(1.0, 1.0);
+        assert_eq!(path.end_tangent(), None);
+
+        // The first control point coincides with the start
+        path.curve_to(1.0, 1.0, 1.0, 3.0, 2.0, 3.0);
+        path.line_to(2.0, 3.0);
+        assert_eq!(path.start_tangent(), Some(Point2D { x: 0.0, y: 1.0 }));
+        assert_eq!(path.end_tangent(), Some(Point2D { x: 1.0, y: 0.0 }));
+
+        path.close_path();
+        let closing = path.end_tangent().unwrap();
+        let expected = -1.0 / 5f64.sqrt();
+        assert!((closing.x - expected).abs() < 1e-12);
+        assert!((closing.y - 2.0 * expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_result_to_svg() {
+        let result = BooleanOperationResult {
+            paths: vec![
+                Arc::new(rect_path(0.0, 0.0, 1.0, 1.0)),
+                Arc::new(rect_path(2.0, 0.0, 3.0, 1.0)),
+            ],
+        };
+        let svg = result_to_svg(&result, FillRule::EvenOdd);
+        assert!(svg.starts_with("<path d=\""));
+        assert!(svg.ends_with(" fill-rule=\"evenodd\"/>"));
+        assert_eq!(svg.matches('M').count(), 2);
+
+        let svg = result_to_svg(&result, FillRule::NonZero);
+        assert!(svg.ends_with(" fill-rule=\"nonzero\"/>"));
+    }
+
+    #[test]
+    fn test_union_cancels_opposite_coincident_edges() {
+        // Both squares are counterclockwise, so the shared edge at x = 1 is
+        // traversed upwards by one and downwards by the other
+        let left = rect_path(0.0, 0.0, 1.0, 1.0);
+        let right = rect_path(1.0, 0.0, 2.0, 1.0);
+
+        let result =
+            boolean_operation(&left, &right, BooleanOperation::Union, FillRule::NonZero).unwrap();
+        assert_eq!(result.paths.len(), 1);
+        let area = result.paths[0].to_kurbo_path().area().abs();
+        assert!((area - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_is_sliver() {
+        let mut sliver = BezPath::new();
+        sliver.move_to((1.0, 0.0));
+        sliver.line_to((1.0, 1.0));
+        sliver.close_path();
+        assert!(is_sliver(&sliver));
+        assert!(!is_sliver(&rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path()));
+    }
+
+    #[test]
+    fn test_extreme_points() {
+        assert_eq!(BezierPath::new().extreme_points(), None);
+
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.0, 2.0, 2.0, 0.0);
+        path.close_path();
+        let extremes = path.extreme_points().unwrap();
+        // The top of the arch is on the curve, not at a control point
+        assert!((extremes.top.x - 1.0).abs() < 1e-9);
+        assert!((extremes.top.y - 1.0).abs() < 1e-9);
+        assert_eq!(extremes.bottom, Point2D { x: 0.0, y: 0.0 });
+        assert_eq!(extremes.left, Point2D { x: 0.0, y: 0.0 });
+        assert_eq!(extremes.right, Point2D { x: 2.0, y: 0.0 });
+    }
+
+    #[test]
+    fn test_resample_uniform() {
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        let points = path.resample_uniform(8, 1e-9);
+        let expected = [
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (2.0, 2.0),
+            (1.0, 2.0),
+            (0.0, 2.0),
+            (0.0, 1.0),
+        ];
+        assert_eq!(points.len(), expected.len());
+        for (p, (x, y)) in points.iter().zip(expected) {
+            assert!((p.x - x).abs() < 1e-6 && (p.y - y).abs() < 1e-6);
+        }
+
+        assert!(path.resample_uniform(1, 1e-9).is_empty());
+        let open = BezierPath::new();
+        open.move_to(0.0, 0.0);
+        open.line_to(1.0, 0.0);
+        assert!(open.resample_uniform(4, 1e-9).is_empty());
+    }
+
+    #[test]
+    fn test_engine_info() {
+        let info = engine_info();
+        assert!(!info.linesweeper_version.is_empty());
+        assert!(!info.kurbo_version.is_empty());
+        assert!(info.supports_curves);
+    }
+
+    #[test]
+    fn test_default_fill_rule() {
+        let path = rect_path(0.0, 0.0, 4.0, 4.0);
+        path.move_to(1.0, 1.0);
+        path.line_to(3.0, 1.0);
+        path.line_to(3.0, 3.0);
+        path.line_to(1.0, 3.0);
+        path.close_path();
+
+        assert_eq!(path.default_fill_rule(), FillRule::NonZero);
+        assert!(path.contains_default(2.0, 2.0));
+
+        path.set_default_fill_rule(FillRule::EvenOdd);
+        assert_eq!(path.default_fill_rule(), FillRule::EvenOdd);
+        assert!(!path.contains_default(2.0, 2.0));
+        assert!(path.contains_default(0.5, 0.5));
+    }
+
+    #[test]
+    fn test_filled_area() {
+        let outer = rect_path(0.0, 0.0, 4.0, 4.0).to_kurbo_path();
+        let hole = rect_path(1.0, 1.0, 3.0, 3.0).to_kurbo_path();
+        let island = rect_path(1.5, 1.5, 2.5, 2.5).to_kurbo_path();
+        assert!((filled_area(std::slice::from_ref(&outer)) - 16.0).abs() < 1e-9);
+        assert!((filled_area(&[outer.clone(), hole.reverse_subpaths()]) - 12.0).abs() < 1e-9);
+        // Same orientation for the hole still counts it as a hole
+        assert!((filled_area(&[outer, hole, island]) - 13.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_coverage_in_rect() {
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        let coverage = path
+            .coverage_in_rect(1.0, 0.0, 2.0, 2.0, FillRule::NonZero)
+            .unwrap();
+        assert!((coverage - 0.5).abs() < 1e-9);
+        assert!(path
+            .coverage_in_rect(0.0, 0.0, 0.0, 1.0, FillRule::NonZero)
+            .is_err());
+    }
+
+    #[test]
+    fn test_reserve() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
+        path.reserve(1000).unwrap();
+        path.line_to(2.0, 2.0);
+        assert_eq!(path.get_segments().len(), 6);
+        assert_eq!(
+            path.get_segments()[..5],
+            rect_path(0.0, 0.0, 1.0, 1.0).get_segments()[..]
+        );
+
+        let result = path.reserve(u64::MAX);
+        assert!(matches!(result, Err(LineSweeperError::InvalidArgument(_))));
+        assert_eq!(path.get_segments().len(), 6);
+    }
+
+    #[test]
+    fn test_segments_by_subpath() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
+        path.move_to(2.0, 0.0);
+        path.line_to(3.0, 0.0);
+
+        let groups = path.segments_by_subpath();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], rect_path(0.0, 0.0, 1.0, 1.0).get_segments());
+        assert_eq!(
+            groups[1],
+            vec![
+                PathSegment::MoveTo { x: 2.0, y: 0.0 },
+                PathSegment::LineTo { x: 3.0, y: 0.0 },
+            ]
+        );
+        assert!(BezierPath::new().segments_by_subpath().is_empty());
+    }
+
+    #[test]
+    fn test_transform_subpath() {
+        let path = rect_path(0.0, 0.0, 4.0, 4.0);
+        path.move_to(1.0, 1.0);
+        path.line_to(2.0, 1.0);
+        path.line_to(2.0, 2.0);
+        path.close_path();
+
+        path.transform_subpath(1, 1.0, 0.0, 0.0, 1.0, 1.0, 0.5);
+        let groups = path.segments_by_subpath();
+        assert_eq!(groups[0], rect_path(0.0, 0.0, 4.0, 4.0).get_segments());
+        assert_eq!(groups[1][0], PathSegment::MoveTo { x: 2.0, y: 1.5 });
+        assert_eq!(groups[1][2], PathSegment::LineTo { x: 3.0, y: 2.5 });
+
+        let before = path.get_segments();
+        path.transform_subpath(2, 2.0, 0.0, 0.0, 2.0, 0.0, 0.0);
+        assert_eq!(path.get_segments(), before);
+    }
+
+    #[test]
+    fn test_clip_to_circle() {
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        let result = path.clip_to_circle(0.0, 0.0, 1.0, FillRule::NonZero).unwrap();
+        assert_eq!(result.paths.len(), 1);
+        let segments = result.paths[0].get_segments();
+        assert!(segments
+            .iter()
+            .any(|s| matches!(s, PathSegment::CurveTo { .. })));
+
+        assert!(path.clip_to_circle(0.0, 0.0, 0.0, FillRule::NonZero).is_err());
+    }
+
+    #[test]
+    fn test_estimate_ribbon_width() {
+        let strip = rect_path(0.0, 0.0, 20.0, 1.0);
+        assert!((strip.estimate_ribbon_width(1e-3).unwrap() - 1.0).abs() < 1e-9);
+
+        // A square frame is thin even though its bounding box isn't
+        let frame = rect_path(0.0, 0.0, 10.0, 10.0);
+        frame.move_to(1.0, 1.0);
+        frame.line_to(1.0, 9.0);
+        frame.line_to(9.0, 9.0);
+        frame.line_to(9.0, 1.0);
+        frame.close_path();
+        assert!((frame.estimate_ribbon_width(1e-3).unwrap() - 1.0).abs() < 1e-9);
+
+        assert_eq!(rect_path(0.0, 0.0, 1.0, 1.0).estimate_ribbon_width(1e-3), None);
+        assert_eq!(BezierPath::new().estimate_ribbon_width(1e-3), None);
+    }
+
+    #[test]
+    fn test_from_turtle() {
+        let path = BezierPath::from_turtle("F2 L90 F2 L90 F2 M1 B1".to_string(), 0.5).unwrap();
+        let segments = path.get_segments();
+        let expected = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (-0.5, 1.0), (0.0, 1.0)];
+        assert_eq!(segments.len(), expected.len());
+        assert!(matches!(segments[4], PathSegment::MoveTo { .. }));
+        for (segment, (ex, ey)) in segments.iter().zip(expected) {
+            let (PathSegment::MoveTo { x, y } | PathSegment::LineTo { x, y }) = *segment else {
+                panic!("unexpected segment {segment:?}");
+            };
+            assert!((x - ex).abs() < 1e-12 && (y - ey).abs() < 1e-12);
+        }
+
+        match BezierPath::from_turtle("F1 X2".to_string(), 1.0) {
+            Err(LineSweeperError::ParseError(message)) => assert!(message.contains("position 3")),
+            _ => panic!("expected a parse error"),
+        }
+        assert!(BezierPath::from_turtle("F".to_string(), 1.0).is_err());
+        // The command is checked first, so a bad command with no number is reported as such
+        match BezierPath::from_turtle("F1 X".to_string(), 1.0) {
+            Err(LineSweeperError::ParseError(message)) => assert!(message.contains("unknown")),
+            _ => panic!("expected a parse error"),
+        }
+        for program in ["Fnan", "Finf", "L-inf"] {
+            let result = BezierPath::from_turtle(program.to_string(), 1.0);
+            assert!(matches!(result, Err(LineSweeperError::ParseError(_))));
+        }
+        let result = BezierPath::from_turtle("F1".to_string(), f64::NAN);
+        assert!(matches!(result, Err(LineSweeperError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_boolean_operation_with_area_delta() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1.0, 0.0, 3.0, 2.0);
+
+        let difference =
+            boolean_operation_with_area_delta(&a, &b, BooleanOperation::Difference, FillRule::NonZero)
+                .unwrap();
+        assert!((difference.area_delta + 2.0).abs() < 1e-9);
+
+        let union =
+            boolean_operation_with_area_delta(&a, &b, BooleanOperation::Union, FillRule::NonZero)
+                .unwrap();
+        assert!((union.area_delta - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fill_ambiguity() {
+        let square = rect_path(0.0, 0.0, 4.0, 4.0);
+        assert_eq!(square.fill_ambiguity(), FillAmbiguity::Clear);
+
+        let figure_eight = BezierPath::new();
+        figure_eight.move_to(0.0, 0.0);
+        figure_eight.line_to(2.0, 2.0);
+        figure_eight.line_to(2.0, 0.0);
+        figure_eight.line_to(0.0, 2.0);
+        figure_eight.close_path();
+        assert_eq!(figure_eight.fill_ambiguity(), FillAmbiguity::SelfOverlapping);
+
+        // A hole running the same way as its outer contour
+        let nested = rect_path(0.0, 0.0, 4.0, 4.0);
+        nested.move_to(1.0, 1.0);
+        nested.line_to(3.0, 1.0);
+        nested.line_to(3.0, 3.0);
+        nested.line_to(1.0, 3.0);
+        nested.close_path();
+        assert_eq!(nested.fill_ambiguity(), FillAmbiguity::MixedWinding);
+
+        nested.transform_subpath(1, -1.0, 0.0, 0.0, 1.0, 4.0, 0.0);
+        assert_eq!(nested.fill_ambiguity(), FillAmbiguity::Clear);
+    }
+
+    #[test]
+    fn test_bytes_format() {
+        // MoveTo(1, 2), LineTo(-0.5, 0), ClosePath
+        let data: Vec<u8> = vec![
+            0, 0, 0, 0, 0, 0, 0, 0xf0, 0x3f, 0, 0, 0, 0, 0, 0, 0, 0x40, //
+            1, 0, 0, 0, 0, 0, 0, 0xe0, 0xbf, 0, 0, 0, 0, 0, 0, 0, 0, //
+            4,
+        ];
+        let path = BezierPath::from_bytes(data.clone()).unwrap();
+        assert_eq!(
+            path.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 1.0, y: 2.0 },
+                PathSegment::LineTo { x: -0.5, y: 0.0 },
+                PathSegment::ClosePath,
+            ]
+        );
+        assert_eq!(path.to_bytes(), data);
+
+        assert!(BezierPath::from_bytes(data[..20].to_vec()).is_err());
+        assert!(BezierPath::from_bytes(vec![9]).is_err());
+        assert!(BezierPath::from_bytes(data[17..].to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_enclosing_regular_polygon() {
+        let square = rect_path(-1.0, -1.0, 1.0, 1.0);
+        let enclosing = square.enclosing_regular_polygon(4).unwrap().to_kurbo_path();
+        assert!((enclosing.area().abs() - 4.0).abs() < 1e-6);
+
+        // Every point of the path is inside the triangle
+        let triangle = square.enclosing_regular_polygon(3).unwrap().to_kurbo_path();
+        assert_eq!(triangle.elements().len(), 4);
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                assert_ne!(triangle.winding(Point::new(x * 0.999, y * 0.999)), 0);
+            }
+        }
+
+        assert!(square.enclosing_regular_polygon(2).is_none());
+        assert!(BezierPath::new().enclosing_regular_polygon(5).is_none());
+    }
+
+    #[test]
+    fn test_pixel_snap() {
+        let path = BezierPath::new();
+        path.move_to(0.1, 0.1);
+        path.line_to(10.2, 0.2);
+        path.line_to(10.4, 5.1);
+        path.line_to(3.0, 9.0);
+        path.close_path();
+
+        let snapped = path.pixel_snap(2.0);
+        assert_eq!(
+            snapped.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 0.1, y: 0.0 },
+                PathSegment::LineTo { x: 10.5, y: 0.0 },
+                PathSegment::LineTo { x: 10.5, y: 5.1 },
+                PathSegment::LineTo { x: 3.0, y: 9.0 },
+                PathSegment::ClosePath,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concavity_regions() {
+        // A square with a triangular notch in its top edge
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(4.0, 0.0);
+        path.line_to(4.0, 4.0);
+        path.line_to(2.0, 2.0);
+        path.line_to(0.0, 4.0);
+        path.close_path();
+
+        let dents = path.concavity_regions(FillRule::NonZero).unwrap();
+        assert_eq!(dents.paths.len(), 1);
+        assert!((result_area(&dents) - 4.0).abs() < 1e-9);
+
+        let square = rect_path(0.0, 0.0, 1.0, 1.0);
+        assert!(square.concavity_regions(FillRule::NonZero).unwrap().paths.is_empty());
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        path.move_to(0.5, 0.5);
+        path.line_to(3.0, 1.0);
+        path.line_to(1.0, 1.5);
+        path.close_path();
+        let hull = convex_hull(&path.to_kurbo_path());
+        assert_eq!(
+            hull,
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(3.0, 1.0),
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_corners_selective() {
+        // An L shape, with five convex corners and one concave corner
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(4.0, 0.0);
+        path.line_to(4.0, 2.0);
+        path.line_to(2.0, 2.0);
+        path.line_to(2.0, 4.0);
+        path.line_to(0.0, 4.0);
+        path.close_path();
+        let count_curves = |path: &BezierPath| {
+            path.get_segments()
+                .iter()
+                .filter(|s| matches!(s, PathSegment::CurveTo { .. }))
+                .count()
+        };
+
+        assert_eq!(count_curves(&path.round_corners_selective(0.5, true, false)), 5);
+        assert_eq!(count_curves(&path.round_corners_selective(0.5, false, true)), 1);
+        assert_eq!(count_curves(&path.round_corners_selective(0.5, true, true)), 6);
+
+        // Rounding a square's corners removes (4 - π) r² of area
+        let rounded = rect_path(0.0, 0.0, 4.0, 4.0).round_corners_selective(1.0, true, false);
+        let area = rounded.to_kurbo_path().area();
+        assert!((area - (16.0 - (4.0 - std::f64::consts::PI))).abs() < 1e-3);
+
+        // Holes that run the other way still see their own corners as concave
+        let frame = rect_path(0.0, 0.0, 4.0, 4.0);
+        frame.move_to(1.0, 1.0);
+        frame.line_to(1.0, 3.0);
+        frame.line_to(3.0, 3.0);
+        frame.line_to(3.0, 1.0);
+        frame.close_path();
+        assert_eq!(count_curves(&frame.round_corners_selective(0.5, true, false)), 4);
+        let segments = frame.round_corners_selective(0.5, true, false).segments_by_subpath();
+        assert!(segments[1]
+            .iter()
+            .all(|s| !matches!(s, PathSegment::CurveTo { .. })));
+    }
+
+    #[test]
+    fn test_planar_edges() {
+        // A bow tie crosses itself once, at (1, 1)
+        let bow_tie = BezierPath::new();
+        bow_tie.move_to(0.0, 0.0);
+        bow_tie.line_to(2.0, 2.0);
+        bow_tie.line_to(2.0, 0.0);
+        bow_tie.line_to(0.0, 2.0);
+        bow_tie.close_path();
+
+        let edges = bow_tie.planar_edges().unwrap();
+        assert_eq!(edges.len(), 2);
+        for edge in &edges {
+            let segments = edge.get_segments();
+            let (PathSegment::MoveTo { x, y }, Some(PathSegment::LineTo { x: ex, y: ey })) =
+                (segments[0], segments.last().copied())
+            else {
+                panic!("unexpected edge {segments:?}");
+            };
+            assert!((x - 1.0).abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+            assert!((ex - 1.0).abs() < 1e-9 && (ey - 1.0).abs() < 1e-9);
+        }
+
+        // A simple closed path comes back whole
+        let square = rect_path(0.0, 0.0, 1.0, 1.0);
+        let edges = square.planar_edges().unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].get_segments().len(), 5);
+
+        let nan = BezierPath::new();
+        nan.move_to(f64::NAN, 0.0);
+        nan.line_to(1.0, 0.0);
+        assert!(matches!(nan.planar_edges(), Err(LineSweeperError::NaNInput)));
+    }
+
+    #[test]
+    fn test_segment_intersections_curves() {
+        let arch = PathSeg::Quad(kurbo::QuadBez::new((0.0, 0.0), (1.0, 2.0), (2.0, 0.0)));
+        let line = PathSeg::Line(kurbo::Line::new((0.0, 0.5), (2.0, 0.5)));
+        let hits = segment_intersections(&arch, &line, 1e-9);
+        assert_eq!(hits.len(), 2);
+        for (ta, tb) in hits {
+            assert!(arch.eval(ta).distance(line.eval(tb)) < 1e-6);
+            assert!((arch.eval(ta).y - 0.5).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_same_topology() {
+        let result = |paths: Vec<BezierPath>| BooleanOperationResult {
+            paths: paths.into_iter().map(Arc::new).collect(),
+        };
+        let framed = result(vec![
+            rect_path(0.0, 0.0, 4.0, 4.0),
+            rect_path(1.0, 1.0, 3.0, 3.0),
+            rect_path(10.0, 0.0, 11.0, 1.0),
+        ]);
+        let nudged = result(vec![
+            rect_path(10.0, 0.0, 11.0, 1.0),
+            rect_path(1.0, 1.0, 3.0, 3.001),
+            rect_path(0.0, 0.0, 4.0, 4.0),
+        ]);
+        assert!(same_topology(&framed, &nudged, 0.01));
+        assert!(!same_topology(&framed, &nudged, 1e-6));
+
+        // Same boxes, but the small square is beside the big one instead of inside
+        let flat = result(vec![
+            rect_path(0.0, 0.0, 4.0, 4.0),
+            rect_path(10.0, 0.0, 11.0, 1.0),
+        ]);
+        assert!(!same_topology(&framed, &flat, 0.01));
+    }
+
+    #[test]
+    fn test_regular_polygon_and_star() {
+        let rotation = std::f64::consts::FRAC_PI_4;
+        let square = BezierPath::regular_polygon(1.0, 1.0, 2f64.sqrt(), 4, rotation);
+        let area = square.to_kurbo_path().area();
+        assert!((area - 4.0).abs() < 1e-9);
+        match square.get_segments()[0] {
+            PathSegment::MoveTo { x, y } => {
+                assert!((x - 2.0).abs() < 1e-12 && (y - 2.0).abs() < 1e-12)
+            }
+            _ => panic!("expected a MoveTo"),
+        }
+
+        let star = BezierPath::star(0.0, 0.0, 2.0, 1.0, 5, 0.0);
+        assert_eq!(star.get_segments().len(), 11);
+        assert!(!star.is_convex(1e-6));
+
+        assert!(BezierPath::regular_polygon(0.0, 0.0, 1.0, 2, 0.0).get_segments().is_empty());
+        assert!(BezierPath::star(0.0, 0.0, 2.0, 1.0, 2, 0.0).get_segments().is_empty());
+    }
+
+    #[test]
+    fn test_morphological_close() {
+        // Two squares separated by a narrow gap merge into one shape
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        path.move_to(2.1, 0.0);
+        path.line_to(4.0, 0.0);
+        path.line_to(4.0, 2.0);
+        path.line_to(2.1, 2.0);
+        path.close_path();
+
+        let closed = path.morphological_close(0.2, FillRule::NonZero, 1e-3).unwrap();
+        assert_eq!(closed.segments_by_subpath().len(), 1);
+        assert!(closed.contains_default(2.05, 1.0));
+        assert!(path.morphological_close(-1.0, FillRule::NonZero, 1e-3).is_err());
+    }
+
+    #[test]
+    fn test_morphological_open() {
+        // A frame whose right side is much thinner than the rest
+        let frame = rect_path(0.0, 0.0, 10.0, 10.0);
+        frame.move_to(2.0, 2.0);
+        frame.line_to(2.0, 8.0);
+        frame.line_to(9.9, 8.0);
+        frame.line_to(9.9, 2.0);
+        frame.close_path();
+
+        let opened = frame.morphological_open(0.5, FillRule::NonZero, 1e-3).unwrap();
+        // The thin side is gone, which opens the hole to the outside
+        assert!(!opened.contains_default(9.95, 5.0));
+        assert!(opened.contains_default(1.0, 5.0));
+        assert_eq!(opened.segments_by_subpath().len(), 1);
+    }
+
+    #[test]
+    fn test_boolean_context() {
+        let context = BooleanContext::new();
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1.0, 1.0, 3.0, 3.0);
+        for operation in [
+            BooleanOperation::Union,
+            BooleanOperation::Intersection,
+            BooleanOperation::Difference,
+            BooleanOperation::Xor,
+        ] {
+            let expected = boolean_operation(&a, &b, operation, FillRule::NonZero).unwrap();
+            let result = context.op(&a, &b, operation, FillRule::NonZero).unwrap();
+            assert!(same_topology(&result, &expected, 1e-9));
+        }
+
+        // Smaller inputs are copied into the buffers already allocated
+        let buffers = |context: &BooleanContext| {
+            let scratch = lock(&context.scratch);
+            (scratch.0.elements().as_ptr(), scratch.1.elements().as_ptr())
+        };
+        let before = buffers(&context);
+        let small = BezierPath::new();
+        small.move_to(0.0, 0.0);
+        small.line_to(1.0, 0.0);
+        context.op(&small, &b, BooleanOperation::Union, FillRule::NonZero).unwrap();
+        assert_eq!(buffers(&context), before);
+    }
+
+    #[test]
+    fn test_copy_closed_into() {
+        let mut buffer = rect_path(5.0, 5.0, 6.0, 6.0).to_kurbo_path();
         let path = BezierPath::new();
+        path.move_to(1.0, 2.0);
+        path.line_to(3.0, 2.0);
+        path.line_to(3.0, 4.0);
         path.move_to(0.0, 0.0);
         path.line_to(1.0, 0.0);
         path.line_to(1.0, 1.0);
         path.close_path();
+        path.move_to(7.0, 7.0);
+        path.copy_closed_into(&mut buffer);
+        assert_eq!(buffer, close_subpaths(&path.to_kurbo_path()));
+    }
+
+    #[test]
+    fn test_boolean_operation_keep_open() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        a.move_to(-1.0, -1.0);
+        a.line_to(3.0, 3.0);
+        let b = rect_path(1.0, 0.0, 3.0, 2.0);
+
+        let kept = boolean_operation_keep_open(&a, &b, BooleanOperation::Union, FillRule::NonZero)
+            .unwrap();
+        assert_eq!(kept.result.paths.len(), 1);
+        assert!((result_area(&kept.result) - 6.0).abs() < 1e-9);
+        assert_eq!(kept.open_paths.len(), 1);
+        assert_eq!(
+            kept.open_paths[0].get_segments(),
+            vec![
+                PathSegment::MoveTo { x: -1.0, y: -1.0 },
+                PathSegment::LineTo { x: 3.0, y: 3.0 },
+            ]
+        );
+
+        // The plain operation closes the line, which then encloses nothing
+        let plain = boolean_operation(&a, &b, BooleanOperation::Union, FillRule::NonZero).unwrap();
+        assert!(same_topology(&plain, &kept.result, 1e-9));
+    }
+
+    #[test]
+    fn test_partition_open_subpaths() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        let mut mixed = path.clone();
+        mixed.move_to((5.0, 5.0));
+        mixed.line_to((6.0, 6.0));
+        let (closed, open) = partition_open_subpaths(&mixed);
+        assert_eq!(closed, path);
+        assert_eq!(open.len(), 1);
+        assert!(has_open_subpaths(&mixed));
+        assert!(!has_open_subpaths(&path));
+    }
+
+    #[test]
+    fn test_roundness() {
+        let circle = BezierPath::from_kurbo_path(kurbo::Circle::new((0.0, 0.0), 5.0).to_path(1e-9));
+        assert!((circle.roundness(1e-9) - 1.0).abs() < 1e-3);
+
+        let square = rect_path(0.0, 0.0, 1.0, 1.0);
+        assert!((square.roundness(1e-9) - std::f64::consts::PI / 4.0).abs() < 1e-9);
+
+        let sliver = rect_path(0.0, 0.0, 100.0, 0.01);
+        assert!(sliver.roundness(1e-9) < 1e-3);
+        assert_eq!(BezierPath::new().roundness(1e-9), 0.0);
+    }
+
+    #[test]
+    fn test_clamp_to_bounds() {
+        let path = BezierPath::new();
+        path.move_to(-5.0, 5.0);
+        path.curve_to(5.0, 20.0, 12.0, 5.0, 8.0, 8.0);
+        path.close_path();
+
+        let clamped = path.clamp_to_bounds(10.0, 10.0, -10.0, -10.0);
+        assert_eq!(
+            clamped.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 0.0, y: 5.0 },
+                PathSegment::CurveTo {
+                    cp1_x: 5.0,
+                    cp1_y: 10.0,
+                    cp2_x: 10.0,
+                    cp2_y: 5.0,
+                    x: 8.0,
+                    y: 8.0,
+                },
+                PathSegment::ClosePath,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_curve_loops() {
+        let looped = BezierPath::new();
+        looped.move_to(0.0, 0.0);
+        looped.curve_to(3.0, 2.0, -1.0, 2.0, 2.0, 0.0);
+        assert!(looped.has_curve_loops());
+
+        // Symmetric control points meeting at a point give a cusp
+        let cusped = BezierPath::new();
+        cusped.move_to(0.0, 0.0);
+        cusped.curve_to(2.0, 2.0, 0.0, 2.0, 2.0, 0.0);
+        assert!(cusped.has_curve_loops());
+
+        let arch = BezierPath::new();
+        arch.move_to(0.0, 0.0);
+        arch.curve_to(0.0, 2.0, 2.0, 2.0, 2.0, 0.0);
+        // A retracted handle is not a cusp
+        arch.curve_to(2.0, 0.0, 3.0, 1.0, 4.0, 0.0);
+        arch.quad_to(5.0, 5.0, 6.0, 0.0);
+        assert!(!arch.has_curve_loops());
+    }
+
+    #[test]
+    fn test_from_svg() {
+        let path = BezierPath::from_svg("M0,0 L10,0 C10,5 5,10 0,10 Z".to_string()).unwrap();
+        assert_eq!(path.get_segments().len(), 4);
+
+        // Relative commands and shorthands expand against the current point
+        let relative = BezierPath::from_svg("m1 1 h2 v2 s-1 1 -2 0 t-1 -1 z".to_string()).unwrap();
+        assert_eq!(
+            relative.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 1.0, y: 1.0 },
+                PathSegment::LineTo { x: 3.0, y: 1.0 },
+                PathSegment::LineTo { x: 3.0, y: 3.0 },
+                PathSegment::CurveTo {
+                    cp1_x: 3.0,
+                    cp1_y: 3.0,
+                    cp2_x: 2.0,
+                    cp2_y: 4.0,
+                    x: 1.0,
+                    y: 3.0,
+                },
+                PathSegment::QuadTo { cp_x: 1.0, cp_y: 3.0, x: 0.0, y: 2.0 },
+                PathSegment::ClosePath,
+            ]
+        );
+
+        assert!(matches!(
+            BezierPath::from_svg("M0,0 L10".to_string()),
+            Err(LineSweeperError::ParseError(_))
+        ));
+        assert!(BezierPath::from_svg("M0,0 X1,1".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_boolean_on_subpaths() {
+        let path = rect_path(10.0, 10.0, 11.0, 11.0);
+        path.move_to(0.0, 0.0);
+        path.line_to(2.0, 0.0);
+        path.line_to(2.0, 2.0);
+        path.line_to(0.0, 2.0);
+        path.close_path();
+        path.move_to(1.0, 0.0);
+        path.line_to(3.0, 0.0);
+        path.line_to(3.0, 2.0);
+        path.line_to(1.0, 2.0);
+        path.close_path();
+        path.move_to(20.0, 20.0);
+        path.quad_to(21.0, 22.0, 22.0, 20.0);
+
+        let result = path
+            .boolean_on_subpaths(vec![2, 1], BooleanOperation::Union, FillRule::NonZero)
+            .unwrap();
+        let subpaths = result.segments_by_subpath();
+        let original = path.segments_by_subpath();
+        assert_eq!(subpaths.len(), 3);
+        assert_eq!(subpaths[0], original[0]);
+        assert_eq!(subpaths[2], original[3]);
+        let merged = path_from_segments(subpaths[1].clone()).unwrap();
+        assert!((merged.area().abs() - 6.0).abs() < 1e-9);
+
+        assert!(path
+            .boolean_on_subpaths(vec![], BooleanOperation::Union, FillRule::NonZero)
+            .is_err());
+        assert!(path
+            .boolean_on_subpaths(vec![4], BooleanOperation::Union, FillRule::NonZero)
+            .is_err());
+        assert!(path
+            .boolean_on_subpaths(vec![1 << 32], BooleanOperation::Union, FillRule::NonZero)
+            .is_err());
+    }
+
+    #[test]
+    fn test_intersection_params() {
+        let square = rect_path(0.0, 0.0, 2.0, 2.0);
+        let diagonal = BezierPath::new();
+        diagonal.move_to(-1.0, 0.0);
+        diagonal.line_to(1.0, 2.0);
+        diagonal.line_to(1.0, 3.0);
+
+        let params = intersection_params(&square, &diagonal);
+        assert_eq!(params.len(), 2);
+        // The diagonal enters through the left edge, the closing segment
+        let left = params.iter().find(|p| p.seg_a == 3).unwrap();
+        assert!(left.point.x.abs() < 1e-9 && (left.point.y - 1.0).abs() < 1e-9);
+        assert!((left.t_a - 0.5).abs() < 1e-9 && (left.t_b - 0.5).abs() < 1e-9);
+        // It leaves through the top edge at the diagonal's corner, reported once
+        let top = params.iter().find(|p| p.seg_a == 2).unwrap();
+        assert_eq!(top.seg_b, 0);
+        assert!((top.t_a - 0.5).abs() < 1e-9 && (top.t_b - 1.0).abs() < 1e-9);
+
+        let far = rect_path(5.0, 5.0, 6.0, 6.0);
+        assert!(intersection_params(&square, &far).is_empty());
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
+        path.move_to(2.0, 0.0);
+        path.quad_to(2.5, 1.0, 3.0, 0.0);
+        path.curve_to(3.0, -0.123456, 2.0, -1.0, 1.0 / 3.0, -0.0001);
+        assert_eq!(
+            path.to_svg(Some(2)),
+            "M0,0 L1,0 L1,1 L0,1 Z M2,0 Q2.5,1 3,0 C3,-0.12 2,-1 0.33,0"
+        );
+        assert!(path.to_svg(None).ends_with(&format!("{},-0.0001", 1.0 / 3.0)));
+        assert_eq!(BezierPath::new().to_svg(None), "");
+    }
+
+    #[test]
+    fn test_to_ascii_preview() {
+        // An L shape: the top right quarter of the square is missing
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(2.0, 0.0);
+        path.line_to(2.0, 1.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(1.0, 2.0);
+        path.line_to(0.0, 2.0);
+        path.close_path();
+        assert_eq!(path.to_ascii_preview(4, 2, FillRule::NonZero), "██  \n████");
+        assert_eq!(path.to_ascii_preview(0, 2, FillRule::NonZero), "");
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.0, 2.0, 2.0, 0.0);
+        let bbox = path.bounding_box().unwrap();
+        assert_eq!((bbox.min_x, bbox.min_y, bbox.max_x), (0.0, 0.0, 2.0));
+        // The curve peaks halfway to its control point
+        assert!((bbox.max_y - 1.0).abs() < 1e-12);
+
+        assert!(BezierPath::new().bounding_box().is_none());
+    }
+
+    #[test]
+    fn test_svg_roundtrip_ok() {
+        let path = rect_path(0.1, 0.2, 1.0 / 3.0, 1e-7);
+        path.move_to(-1e12, 5.0);
+        path.curve_to(2.0, 3.0, 4.0, 5.0, 6.5, -7.25);
+        path.quad_to(1.0, 1.0, 0.0, 0.0);
+        assert!(path.svg_roundtrip_ok(0.0));
+        assert!(BezierPath::new().svg_roundtrip_ok(0.0));
+    }
+
+    #[test]
+    fn test_elements_approx_eq() {
+        let a = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        let b = rect_path(0.0, 0.0, 1.0, 1.0 + 1e-6).to_kurbo_path();
+        assert!(elements_approx_eq(&a, &b, 1e-5));
+        assert!(!elements_approx_eq(&a, &b, 1e-7));
+
+        let mut open = a.clone();
+        open.truncate(4);
+        assert!(!elements_approx_eq(&a, &open, 1.0));
+    }
+
+    #[test]
+    fn test_apply_transform() {
+        let path = BezierPath::new();
+        path.move_to(1.0, 0.0);
+        path.curve_to(1.0, 1.0, 0.0, 2.0, 0.0, 3.0);
+
+        // Rotate a quarter turn counterclockwise and move right by 10
+        let rotated = path.transformed(0.0, 1.0, -1.0, 0.0, 10.0, 0.0);
+        assert_eq!(
+            rotated.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 10.0, y: 1.0 },
+                PathSegment::CurveTo {
+                    cp1_x: 9.0,
+                    cp1_y: 1.0,
+                    cp2_x: 8.0,
+                    cp2_y: 0.0,
+                    x: 7.0,
+                    y: 0.0,
+                },
+            ]
+        );
+        assert_eq!(path.get_segments()[0], PathSegment::MoveTo { x: 1.0, y: 0.0 });
+
+        path.apply_transform(0.0, 1.0, -1.0, 0.0, 10.0, 0.0);
+        assert_eq!(path.get_segments(), rotated.get_segments());
+    }
+
+    #[test]
+    fn test_to_svg_relative() {
+        let path = rect_path(10.0, 10.0, 11.0, 12.0);
+        path.move_to(0.1, 0.2);
+        path.quad_to(0.3, 0.4, 0.5, 0.6);
+        path.curve_to(1.0, 1.0, 2.0, 2.0, 1.0 / 3.0, 0.0);
+        assert_eq!(
+            path.to_svg_relative(2),
+            "m10,10 l1,0 l0,2 l-1,0 z m-9.9,-9.8 q0.2,0.2 0.4,0.4 c0.5,0.4 1.5,1.4 -0.17,-0.6"
+        );
+    }
+
+    #[test]
+    fn test_union_all() {
+        let paths = vec![
+            Arc::new(rect_path(0.0, 0.0, 2.0, 2.0)),
+            Arc::new(rect_path(1.0, 1.0, 3.0, 3.0)),
+            Arc::new(rect_path(10.0, 10.0, 11.0, 11.0)),
+        ];
+        let result = union_all(paths, FillRule::EvenOdd).unwrap();
+        assert_eq!(result.paths.len(), 2);
+        assert!((result_area(&result) - 8.0).abs() < 1e-9);
+
+        assert!(union_all(Vec::new(), FillRule::NonZero).unwrap().paths.is_empty());
+    }
+
+    #[test]
+    fn test_xor_area() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1.0, 0.0, 3.0, 2.0);
+        assert!((a.xor_area(&b, FillRule::NonZero).unwrap() - 4.0).abs() < 1e-9);
+        assert!(a.xor_area(&a, FillRule::NonZero).unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_area() {
+        let frame = rect_path(0.0, 0.0, 3.0, 3.0);
+        assert_eq!(frame.signed_area(), 9.0);
+        // A clockwise hole
+        frame.move_to(1.0, 1.0);
+        frame.line_to(1.0, 2.0);
+        frame.line_to(2.0, 2.0);
+        frame.line_to(2.0, 1.0);
+        frame.close_path();
+        assert_eq!(frame.signed_area(), 8.0);
+
+        let reversed = rect_path(3.0, 0.0, 0.0, 3.0);
+        assert_eq!(reversed.signed_area(), -9.0);
+        assert_eq!(reversed.area(), 9.0);
+    }
+
+    #[test]
+    fn test_flatten_with() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.quad_to(2.0, 0.0, 2.0, 1.0);
+        path.curve_to(2.0, 2.0, 1.0, 3.0, 0.0, 3.0);
+        path.close_path();
+        let lines_only = |flat: &BezierPath| {
+            flat.get_segments().iter().all(|segment| {
+                !matches!(segment, PathSegment::QuadTo { .. } | PathSegment::CurveTo { .. })
+            })
+        };
+
+        let counted = path.flatten_with(FlattenMethod::UniformCount { count: 4 });
+        assert!(lines_only(&counted));
+        // Move, line, 4 lines per curve, close
+        assert_eq!(counted.get_segments().len(), 11);
+        assert_eq!(counted.get_segments()[9], PathSegment::LineTo { x: 0.0, y: 3.0 });
+
+        let curve = BezierPath::new();
+        curve.move_to(0.0, 0.0);
+        curve.curve_to(0.0, 2.0, 1.0, 3.0, 3.0, 3.0);
+        let even = curve.flatten_with(FlattenMethod::UniformLength { length: 0.1 });
+        assert!(lines_only(&even));
+        let lengths = even.segment_length_stats(1e-9);
+        assert!(lengths.max - lengths.min < 1e-3 && lengths.max <= 0.1);
+
+        let adaptive = path.flatten_with(FlattenMethod::AdaptiveTolerance { tolerance: 1e-3 });
+        assert!(lines_only(&adaptive));
+        assert!((adaptive.area() - path.area()).abs() < 1e-2);
+
+        let single = path.flatten_with(FlattenMethod::UniformLength { length: 0.0 });
+        assert_eq!(single.get_segments().len(), 5);
+
+        // Huge counts stop at the per-curve limit
+        let capped = curve.flatten_with(FlattenMethod::UniformCount { count: u32::MAX });
+        assert_eq!(capped.get_segments().len(), 1 + SPLIT_CURVES_MAX_PIECES);
+    }
+
+    #[test]
+    fn test_contains() {
+        // Two overlapping squares running the same way
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        path.move_to(1.0, 1.0);
+        path.line_to(3.0, 1.0);
+        path.line_to(3.0, 3.0);
+        path.line_to(1.0, 3.0);
+        assert!(path.contains(1.5, 1.5, FillRule::NonZero));
+        assert!(!path.contains(1.5, 1.5, FillRule::EvenOdd));
+        assert!(path.contains(2.5, 2.5, FillRule::EvenOdd));
+        assert!(!path.contains(2.5, 0.5, FillRule::NonZero));
+
+        let circle = BezierPath::from_kurbo_path(kurbo::Circle::new((0.0, 0.0), 1.0).to_path(1e-9));
+        assert!(circle.contains(0.0, 0.99, FillRule::NonZero));
+        assert!(!circle.contains(0.72, 0.72, FillRule::NonZero));
+    }
+
+    #[test]
+    fn test_from_segments() {
+        let segments = vec![
+            PathSegment::MoveTo { x: 0.0, y: 0.0 },
+            PathSegment::LineTo { x: 1.0, y: 0.0 },
+            PathSegment::QuadTo { cp_x: 1.5, cp_y: 0.5, x: 2.0, y: 0.0 },
+            PathSegment::ClosePath,
+        ];
+        let path = BezierPath::from_segments(segments.clone()).unwrap();
+        assert_eq!(path.get_segments(), segments);
+
+        let invalid = vec![PathSegment::LineTo { x: 1.0, y: 0.0 }];
+        assert!(BezierPath::from_segments(invalid.clone()).is_err());
+        assert!(path.set_segments(invalid).is_err());
+        assert_eq!(path.get_segments(), segments);
+
+        path.set_segments(segments[..2].to_vec()).unwrap();
+        assert_eq!(path.get_segments(), segments[..2]);
+    }
+
+    #[test]
+    fn test_is_rectilinear() {
+        let path = rect_path(0.0, 0.0, 2.0, 1.0);
+        assert!(path.is_rectilinear(0.0));
+
+        // The closing edge is slightly slanted
+        let skewed = BezierPath::new();
+        skewed.move_to(0.0, 0.0);
+        skewed.line_to(2.0, 0.0);
+        skewed.line_to(2.0, 1.0);
+        skewed.line_to(0.001, 1.0);
+        skewed.close_path();
+        assert!(!skewed.is_rectilinear(0.0));
+        assert!(skewed.is_rectilinear(0.01));
+
+        path.move_to(5.0, 5.0);
+        path.quad_to(5.0, 6.0, 5.0, 7.0);
+        assert!(!path.is_rectilinear(1.0));
+    }
+
+    #[test]
+    fn test_boolean_operation_rectilinear() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1.0, 1.0, 3.0, 3.0);
+
+        let union = boolean_operation_rectilinear(&a, &b, BooleanOperation::Union).unwrap();
+        assert_eq!(union.paths.len(), 1);
+        let outline = union.paths[0].to_kurbo_path();
+        assert_eq!(outline.area(), 7.0);
+        // Only the 8 corners of the L-shaped outline, no collinear vertices
+        assert_eq!(outline.elements().len(), 9);
+
+        let difference =
+            boolean_operation_rectilinear(&a, &b, BooleanOperation::Difference).unwrap();
+        assert_eq!(result_area(&difference), 3.0);
+
+        // A frame around a hole gives a counterclockwise outer contour and a
+        // clockwise hole
+        let frame = rect_path(-1.0, -1.0, 4.0, 4.0);
+        let xor = boolean_operation_rectilinear(&frame, &a, BooleanOperation::Xor).unwrap();
+        let mut areas: Vec<f64> = xor.paths.iter().map(|p| p.to_kurbo_path().area()).collect();
+        areas.sort_by(f64::total_cmp);
+        assert_eq!(areas, vec![-4.0, 25.0]);
+
+        // Squares touching at a corner stay separate
+        let corner = rect_path(2.0, 2.0, 3.0, 3.0);
+        let touching = boolean_operation_rectilinear(&a, &corner, BooleanOperation::Union).unwrap();
+        assert_eq!(touching.paths.len(), 2);
+
+        let diagonal = BezierPath::new();
+        diagonal.move_to(0.0, 0.0);
+        diagonal.line_to(1.0, 0.0);
+        diagonal.line_to(0.0, 1.0);
+        diagonal.close_path();
+        assert!(boolean_operation_rectilinear(&a, &diagonal, BooleanOperation::Union).is_err());
+    }
+
+    #[test]
+    fn test_boolean_operation_closes_open_subpaths() {
+        let triangle = |x: f64, closed: bool| {
+            let path = BezierPath::new();
+            path.move_to(x, 0.0);
+            path.line_to(x + 2.0, 0.0);
+            path.line_to(x + 1.0, 2.0);
+            if closed {
+                path.close_path();
+            }
+            path
+        };
+        let (open_a, open_b) = (triangle(0.0, false), triangle(1.0, false));
+        let (closed_a, closed_b) = (triangle(0.0, true), triangle(1.0, true));
+
+        let op = BooleanOperation::Union;
+        let open = boolean_operation(&open_a, &open_b, op, FillRule::NonZero).unwrap();
+        let closed = boolean_operation(&closed_a, &closed_b, op, FillRule::NonZero).unwrap();
+        assert!(same_topology(&open, &closed, 1e-9));
+        assert!((result_area(&open) - result_area(&closed)).abs() < 1e-9);
+
+        let ignored = boolean_operation_with_open_subpaths(
+            &open_a,
+            &closed_b,
+            op,
+            FillRule::NonZero,
+            false,
+        )
+        .unwrap();
+        assert!((result_area(&ignored) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_close_subpaths() {
+        let mut path = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        path.move_to((5.0, 5.0));
+        path.line_to((6.0, 5.0));
+        path.line_to((6.0, 6.0));
+        let closed = close_subpaths(&path);
+        assert!(!has_open_subpaths(&closed));
+        assert_eq!(closed.elements().len(), path.elements().len() + 1);
+        assert_eq!(close_subpaths(&closed), closed);
+    }
+
+    #[test]
+    fn test_from_flat_commands() {
+        let path = BezierPath::from_flat_commands(
+            vec![0, 1, 2, 3, 4],
+            vec![0.0, 0.0, 1.0, 0.0, 1.5, 0.5, 2.0, 0.0, 2.5, 0.0, 3.0, 0.5, 3.0, 1.0],
+        )
+        .unwrap();
+        let expected = BezierPath::new();
+        expected.move_to(0.0, 0.0);
+        expected.line_to(1.0, 0.0);
+        expected.quad_to(1.5, 0.5, 2.0, 0.0);
+        expected.curve_to(2.5, 0.0, 3.0, 0.5, 3.0, 1.0);
+        expected.close_path();
+        assert_eq!(path.get_segments(), expected.get_segments());
+
+        assert!(BezierPath::from_flat_commands(vec![0, 1], vec![0.0, 0.0, 1.0]).is_err());
+        assert!(BezierPath::from_flat_commands(vec![0], vec![0.0, 0.0, 1.0]).is_err());
+        assert!(BezierPath::from_flat_commands(vec![0, 7], vec![0.0, 0.0]).is_err());
+        assert!(BezierPath::from_flat_commands(vec![1], vec![0.0, 0.0]).is_err());
+        assert!(BezierPath::from_flat_commands(vec![], vec![]).unwrap().get_segments().is_empty());
+    }
+
+    #[test]
+    fn test_contour_infos() {
+        let outer = rect_path(0.0, 0.0, 3.0, 3.0);
+        let hole = rect_path(2.0, 1.0, 1.0, 2.0);
+        let island = rect_path(10.0, 0.0, 11.0, 1.0);
+        let result = BooleanOperationResult {
+            paths: vec![Arc::new(hole), Arc::new(outer), Arc::new(island)],
+        };
+        assert_eq!(
+            contour_infos(&result),
+            vec![
+                ContourInfo { signed_area: -1.0, is_hole: true, winding: -1 },
+                ContourInfo { signed_area: 9.0, is_hole: false, winding: 1 },
+                ContourInfo { signed_area: 1.0, is_hole: false, winding: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_flat_commands() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.5, 0.5, 2.0, 0.0);
+        path.curve_to(2.5, 0.0, 3.0, 0.5, 3.0, 1.0);
+        path.close_path();
+        let flat = path.to_flat_commands();
+        assert_eq!(flat.tags, vec![0, 2, 3, 4]);
+        assert_eq!(flat.coords.len(), 12);
+
+        let rebuilt = BezierPath::from_flat_commands(flat.tags, flat.coords).unwrap();
+        assert_eq!(rebuilt.get_segments(), path.get_segments());
+    }
+
+    #[test]
+    fn test_catch_engine_panic() {
+        assert_eq!(catch_engine_panic(|| 42).unwrap(), 42);
+        match catch_engine_panic(|| -> i32 { panic!("degenerate sliver") }) {
+            Err(LineSweeperError::InternalError(message)) => {
+                assert!(message.contains("degenerate sliver"))
+            }
+            _ => panic!("expected an internal error"),
+        }
+        let index = 3;
+        match catch_engine_panic(|| -> i32 { panic!("bad index {index}") }) {
+            Err(LineSweeperError::InternalError(message)) => {
+                assert!(message.contains("bad index 3"))
+            }
+            _ => panic!("expected an internal error"),
+        }
+    }
+
+    #[test]
+    fn test_moment_of_inertia() {
+        // A 4×2 rectangle: b·h³/12 about its horizontal axis
+        let rect = rect_path(1.0, 1.0, 5.0, 3.0);
+        let moments = rect.moment_of_inertia().unwrap();
+        assert!((moments.ixx - 4.0 * 8.0 / 12.0).abs() < 1e-9);
+        assert!((moments.iyy - 2.0 * 64.0 / 12.0).abs() < 1e-9);
+        assert!(moments.ixy.abs() < 1e-9);
+
+        // A centered square hole subtracts its own moments, whichever way it runs
+        let frame = rect_path(0.0, 0.0, 4.0, 4.0);
+        frame.move_to(1.0, 1.0);
+        frame.line_to(3.0, 1.0);
+        frame.line_to(3.0, 3.0);
+        frame.line_to(1.0, 3.0);
+        frame.close_path();
+        let moments = frame.moment_of_inertia().unwrap();
+        assert!((moments.ixx - (256.0 - 16.0) / 12.0).abs() < 1e-9);
+
+        assert!(BezierPath::new().moment_of_inertia().is_none());
+    }
+
+    #[test]
+    fn test_poisoned_lock_recovers() {
+        let path = Arc::new(rect_path(0.0, 0.0, 1.0, 1.0));
+        let holder = Arc::clone(&path);
+        let _ = std::thread::spawn(move || {
+            let _guard = lock(&holder.path);
+            panic!("panic while holding the lock");
+        })
+        .join();
+        assert!(path.path.is_poisoned());
+
+        assert_eq!(path.get_segments().len(), 5);
+        path.move_to(2.0, 2.0);
+        assert_eq!(path.get_segments().len(), 6);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let circle =
+            BezierPath::from_kurbo_path(kurbo::Circle::new((0.0, 0.0), 10.0).to_path(1e-9));
+        let coarse = circle.flatten(0.1).get_segments();
+        let fine = circle.flatten(0.001).get_segments();
+        assert!(fine.len() > coarse.len());
+        for segment in coarse.iter().chain(&fine) {
+            assert!(matches!(
+                segment,
+                PathSegment::MoveTo { .. } | PathSegment::LineTo { .. } | PathSegment::ClosePath
+            ));
+        }
+        // Vertices stay on the cubic approximation, which is within 0.03% of the circle
+        for segment in &fine {
+            if let PathSegment::LineTo { x, y } = segment {
+                assert!((x.hypot(*y) - 10.0).abs() < 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_about() {
+        let path = rect_path(2.0, 2.0, 4.0, 4.0);
+        let rotated = path.rotate_about(3.0, 3.0, std::f64::consts::FRAC_PI_2);
+
+        // A square rotated a quarter turn about its center covers itself
+        let bbox = rotated.bounding_box().unwrap();
+        assert!((bbox.min_x - 2.0).abs() < 1e-9 && (bbox.max_y - 4.0).abs() < 1e-9);
+        let PathSegment::MoveTo { x, y } = rotated.get_segments()[0] else {
+            panic!("expected MoveTo");
+        };
+        assert!((x - 4.0).abs() < 1e-9 && (y - 2.0).abs() < 1e-9);
+        assert_eq!(path.get_segments()[0], PathSegment::MoveTo { x: 2.0, y: 2.0 });
+    }
+
+    #[test]
+    fn test_control_polygon() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.0, 2.0, 2.0, 0.0);
+        path.curve_to(3.0, 0.0, 4.0, 1.0, 4.0, 2.0);
+        path.close_path();
+
+        let points: Vec<(f64, f64)> = path
+            .control_polygon()
+            .get_segments()
+            .into_iter()
+            .map(|segment| match segment {
+                PathSegment::MoveTo { x, y } | PathSegment::LineTo { x, y } => (x, y),
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                (0.0, 0.0),
+                (1.0, 2.0),
+                (2.0, 0.0),
+                (3.0, 0.0),
+                (4.0, 1.0),
+                (4.0, 2.0),
+                (0.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reversed() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(4.0, 0.0);
+        path.curve_to(5.0, 1.0, 5.0, 3.0, 4.0, 4.0);
+        path.close_path();
+
+        let reversed = path.reversed();
+        assert_eq!(
+            reversed.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 4.0, y: 4.0 },
+                PathSegment::CurveTo {
+                    cp1_x: 5.0,
+                    cp1_y: 3.0,
+                    cp2_x: 5.0,
+                    cp2_y: 1.0,
+                    x: 4.0,
+                    y: 0.0,
+                },
+                PathSegment::LineTo { x: 0.0, y: 0.0 },
+                PathSegment::ClosePath,
+            ]
+        );
+        assert!((reversed.signed_area() + path.signed_area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contour_element_counts() {
+        let triangle = BezierPath::new();
+        triangle.move_to(0.0, 0.0);
+        triangle.line_to(1.0, 0.0);
+        triangle.line_to(0.0, 1.0);
+        triangle.close_path();
+        let result = BooleanOperationResult {
+            paths: vec![Arc::new(rect_path(0.0, 0.0, 1.0, 1.0)), Arc::new(triangle)],
+        };
+        let counts = contour_element_counts(&result);
+        assert_eq!(counts, vec![result.paths[0].get_segments().len() as u64, 4]);
+    }
+
+    #[test]
+    fn test_extend_and_concat() {
+        let a = Arc::new(rect_path(0.0, 0.0, 1.0, 1.0));
+        let b = Arc::new(rect_path(2.0, 0.0, 3.0, 1.0));
+        let concatenated = BezierPath::concat(vec![a.clone(), b.clone()]);
+
+        a.extend(&b);
+        assert_eq!(a.get_segments(), concatenated.get_segments());
+        assert_eq!(a.get_segments().len(), 2 * b.get_segments().len());
+        assert_eq!(a.get_segments()[5], b.get_segments()[0]);
+
+        b.extend(&b);
+        assert_eq!(b.get_segments().len(), a.get_segments().len());
+    }
+
+    #[test]
+    fn test_boolean_operation_with_tolerance() {
+        // The right edges are 1e-4 apart, leaving a thin sliver after the difference
+        let wide = rect_path(0.0, 0.0, 1.0 + 1e-4, 1.0);
+        let square = rect_path(0.0, 0.0, 1.0, 1.0);
+        let op = BooleanOperation::Difference;
+
+        let fine = boolean_operation_with_tolerance(&wide, &square, op, FillRule::NonZero, 1e-7)
+            .unwrap();
+        assert_eq!(fine.paths.len(), 1);
+        assert!((result_area(&fine) - 1e-4).abs() < 1e-6);
+
+        let coarse = boolean_operation_with_tolerance(&wide, &square, op, FillRule::NonZero, 1e-2)
+            .unwrap();
+        assert!(coarse.paths.is_empty());
+
+        assert!(matches!(
+            boolean_operation_with_tolerance(&wide, &square, op, FillRule::NonZero, 0.0),
+            Err(LineSweeperError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_physics_ready() {
+        let square = rect_path(0.0, 0.0, 2.0, 2.0);
+        let readiness = square.physics_ready(8);
+        assert!(readiness.ready);
+        assert_eq!(readiness.vertex_count, 4);
+        assert!(!square.physics_ready(3).within_vertex_limit);
+
+        let bowtie = BezierPath::new();
+        bowtie.move_to(0.0, 0.0);
+        bowtie.line_to(2.0, 2.0);
+        bowtie.line_to(2.0, 0.0);
+        bowtie.line_to(0.0, 2.0);
+        bowtie.close_path();
+        let readiness = bowtie.physics_ready(8);
+        assert!(readiness.is_closed && !readiness.is_simple && !readiness.ready);
+
+        let open = BezierPath::new();
+        open.move_to(0.0, 0.0);
+        open.line_to(1.0, 0.0);
+        open.line_to(f64::NAN, 1.0);
+        let readiness = open.physics_ready(8);
+        assert!(!readiness.is_closed && !readiness.is_finite && !readiness.has_area);
+    }
+
+    #[test]
+    fn test_to_triangle_vertices_f32() {
+        let triangles_area = |vertices: &[f32]| -> f64 {
+            assert_eq!(vertices.len() % 6, 0);
+            vertices
+                .chunks(6)
+                .map(|t| {
+                    let t: Vec<f64> = t.iter().map(|&v| f64::from(v)).collect();
+                    0.5 * ((t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1]))
+                })
+                .inspect(|area| assert!(*area > 0.0))
+                .sum()
+        };
+
+        // A square with a square hole wound the same way
+        let path = rect_path(0.0, 0.0, 4.0, 4.0);
+        path.extend(&rect_path(1.0, 1.0, 2.0, 2.0));
+        let even_odd = path.to_triangle_vertices_f32(FillRule::EvenOdd, 0.01);
+        assert!((triangles_area(&even_odd) - 15.0).abs() < 1e-6);
+        let non_zero = path.to_triangle_vertices_f32(FillRule::NonZero, 0.01);
+        assert!((triangles_area(&non_zero) - 16.0).abs() < 1e-6);
+
+        // A bowtie crosses itself, and each lobe is filled
+        let bowtie = BezierPath::new();
+        bowtie.move_to(0.0, 0.0);
+        bowtie.line_to(2.0, 2.0);
+        bowtie.line_to(2.0, 0.0);
+        bowtie.line_to(0.0, 2.0);
+        bowtie.close_path();
+        let vertices = bowtie.to_triangle_vertices_f32(FillRule::NonZero, 0.01);
+        assert!((triangles_area(&vertices) - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_simplify() {
+        // A figure eight fills two lobes that touch at the crossing
+        let bowtie = BezierPath::new();
+        bowtie.move_to(0.0, 0.0);
+        bowtie.line_to(2.0, 2.0);
+        bowtie.line_to(2.0, 0.0);
+        bowtie.line_to(0.0, 2.0);
+        bowtie.close_path();
+        let result = simplify(&bowtie, FillRule::NonZero).unwrap();
+        assert_eq!(result.paths.len(), 2);
+        assert!((result_area(&result) - 2.0).abs() < 1e-9);
+
+        // Under even-odd, a nested square wound the same way is a hole
+        let path = rect_path(0.0, 0.0, 4.0, 4.0);
+        path.extend(&rect_path(1.0, 1.0, 2.0, 2.0));
+        let result = simplify(&path, FillRule::EvenOdd).unwrap();
+        assert_eq!(result.paths.len(), 2);
+        assert!((result_area(&result) - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_remove_backtracks() {
+        // A square whose bottom edge runs out to (3, 0) and back to (2, 0)
+        let mut spiked = BezPath::new();
+        spiked.move_to((0.0, 0.0));
+        spiked.line_to((3.0, 0.0));
+        spiked.line_to((2.0, 0.0));
+        spiked.line_to((2.0, 2.0));
+        spiked.line_to((0.0, 2.0));
+        spiked.close_path();
+        let merged = remove_backtracks(&spiked);
+        let points: Vec<Point> = merged.elements().iter().filter_map(|el| el.end_point()).collect();
+        assert_eq!(points.len(), 5);
+        assert!(!points.contains(&Point::new(3.0, 0.0)));
+        assert!((merged.area() - 4.0).abs() < 1e-12);
+
+        // Contours without overlapping edges are untouched
+        let square = rect_path(0.0, 0.0, 1.0, 1.0).to_kurbo_path();
+        assert_eq!(remove_backtracks(&square), square);
+    }
+
+    #[test]
+    fn test_union_has_no_doubled_edges() {
+        // The rectangle's bottom edge overlaps part of the top of the L's foot
+        let l_shape = BezierPath::new();
+        l_shape.move_to(0.0, 0.0);
+        l_shape.line_to(3.0, 0.0);
+        l_shape.line_to(3.0, 1.0);
+        l_shape.line_to(1.0, 1.0);
+        l_shape.line_to(1.0, 3.0);
+        l_shape.line_to(0.0, 3.0);
+        l_shape.close_path();
+        let rect = rect_path(2.0, 1.0, 4.0, 2.0);
+        let result =
+            boolean_operation(&l_shape, &rect, BooleanOperation::Union, FillRule::NonZero)
+                .unwrap();
+        for path in &result.paths {
+            let path = path.to_kurbo_path();
+            assert_eq!(remove_backtracks(&path), path);
+        }
+    }
 
+    #[test]
+    fn test_segment_count_and_at() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
         let segments = path.get_segments();
-        assert_eq!(segments.len(), 4);
+        assert_eq!(path.segment_count(), segments.len() as u64);
+        for (i, segment) in segments.iter().enumerate() {
+            assert_eq!(path.segment_at(i as u64).as_ref(), Some(segment));
+        }
+        assert_eq!(path.segment_at(segments.len() as u64), None);
+        assert_eq!(path.segment_at(u64::MAX), None);
+        assert_eq!(BezierPath::new().segment_count(), 0);
+    }
 
-        match &segments[0] {
-            PathSegment::MoveTo { x, y } => {
-                assert_eq!(*x, 0.0);
-                assert_eq!(*y, 0.0);
-            }
-            _ => panic!("Expected MoveTo segment"),
+    #[test]
+    fn test_shape_descriptor() {
+        let distance = |a: &[f64], b: &[f64]| -> f64 {
+            a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+        };
+        let square = rect_path(0.0, 0.0, 1.0, 1.0);
+        let descriptor = square.shape_descriptor(8);
+        assert_eq!(descriptor.len(), 8);
+
+        // Moving, scaling and rotating the square leaves the descriptor alone
+        let moved = square.transformed(0.0, 3.0, -3.0, 0.0, 10.0, -4.0);
+        assert!(distance(&descriptor, &moved.shape_descriptor(8)) < 1e-6);
+
+        let circle =
+            BezierPath::from_kurbo_path(kurbo::Circle::new((0.0, 0.0), 1.0).to_path(1e-9));
+        assert!(distance(&descriptor, &circle.shape_descriptor(8)) > 0.01);
+
+        assert!(BezierPath::new().shape_descriptor(8).is_empty());
+        assert!(square.shape_descriptor(0).is_empty());
+        assert_eq!(square.shape_descriptor(u32::MAX).len(), SHAPE_DESCRIPTOR_MAX_BINS as usize);
+    }
+
+    #[test]
+    fn test_is_empty_and_clear() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
+        path.set_default_fill_rule(FillRule::EvenOdd);
+        assert!(!path.is_empty());
+
+        path.clear();
+        assert!(path.is_empty());
+        assert_eq!(path.default_fill_rule(), FillRule::NonZero);
+        assert_eq!(path.get_segments(), BezierPath::new().get_segments());
+
+        path.move_to(1.0, 2.0);
+        assert_eq!(path.get_segments(), vec![PathSegment::MoveTo { x: 1.0, y: 2.0 }]);
+    }
+
+    #[test]
+    fn test_restore_input_points() {
+        let input = rect_path(0.1, 0.3, 0.7, 0.9).to_kurbo_path();
+        let jittered: BezPath = input
+            .iter()
+            .map(|el| map_points(el, |p| Point::new(p.x + 1e-12, p.y - 1e-12)))
+            .collect();
+        let result = BooleanOperationResult {
+            paths: vec![Arc::new(BezierPath::from_kurbo_path(jittered))],
+        };
+        restore_input_points(&result, &[&input, &BezPath::new()], 1e-9);
+        assert_eq!(result.paths[0].to_kurbo_path(), input);
+
+        // Only end points are matched, so a vertex never lands on a control point
+        let mut curve = BezPath::new();
+        curve.move_to((0.0, 0.0));
+        curve.curve_to((1.0, 1.0), (2.0, 1.0), (3.0, 0.0));
+        let mut near = BezPath::new();
+        near.move_to((3.0 + 1e-12, 0.0));
+        near.line_to((1.0 + 1e-12, 1.0));
+        near.line_to((2.0, 1.0 + 1e-12));
+        near.close_path();
+        let result = BooleanOperationResult {
+            paths: vec![Arc::new(BezierPath::from_kurbo_path(near.clone()))],
+        };
+        restore_input_points(&result, &[&curve], 1e-9);
+        let restored = result.paths[0].to_kurbo_path();
+        assert_eq!(restored.elements()[0], PathEl::MoveTo(Point::new(3.0, 0.0)));
+        assert_eq!(restored.elements()[1..], near.elements()[1..]);
+    }
+
+    #[test]
+    fn test_untouched_vertices_keep_exact_coordinates() {
+        let a = rect_path(0.1, 0.3, 0.7, 0.9);
+        let b = rect_path(0.5, 0.1, 1.3, 0.6);
+        let result = boolean_operation(&a, &b, BooleanOperation::Union, FillRule::NonZero).unwrap();
+        assert_eq!(result.paths.len(), 1);
+        let path = result.paths[0].to_kurbo_path();
+        let result_bits: Vec<(u64, u64)> = path
+            .elements()
+            .iter()
+            .filter_map(|el| el.end_point())
+            .map(|p| (p.x.to_bits(), p.y.to_bits()))
+            .collect();
+        // The corners of each square outside the other one are not on any crossing
+        let untouched = [(0.1, 0.3), (0.1, 0.9), (0.7, 0.9), (0.5, 0.1), (1.3, 0.1), (1.3, 0.6)];
+        for (x, y) in untouched {
+            assert!(result_bits.contains(&(f64::to_bits(x), f64::to_bits(y))));
         }
+    }
 
-        match &segments[1] {
-            PathSegment::LineTo { x, y } => {
-                assert_eq!(*x, 1.0);
-                assert_eq!(*y, 0.0);
+    #[test]
+    fn test_duplicate() {
+        let path = rect_path(0.0, 0.0, 1.0, 1.0);
+        path.set_default_fill_rule(FillRule::EvenOdd);
+        let copy = path.duplicate();
+        assert_eq!(copy.get_segments(), path.get_segments());
+        assert_eq!(copy.default_fill_rule(), FillRule::EvenOdd);
+
+        copy.line_to(5.0, 5.0);
+        path.clear();
+        assert!(path.is_empty());
+        assert_eq!(copy.segment_count(), 6);
+    }
+
+    #[test]
+    fn test_flatten_capped() {
+        let circle =
+            BezierPath::from_kurbo_path(kurbo::Circle::new((0.0, 0.0), 10.0).to_path(1e-9));
+        let full = circle.flatten_capped(0.001, 10_000);
+        assert!(!full.truncated);
+        assert_eq!(full.path.get_segments(), circle.flatten(0.001).get_segments());
+
+        let capped = circle.flatten_capped(0.001, 40);
+        assert!(capped.truncated);
+        let segments = capped.path.get_segments();
+        assert!(segments.len() <= 41);
+        assert_eq!(segments.last(), Some(&PathSegment::ClosePath));
+
+        // One line per curve is the coarsest possible result
+        let coarsest = circle.flatten_capped(0.001, 2);
+        assert!(coarsest.truncated);
+        assert_eq!(coarsest.path.segment_count(), circle.segment_count());
+    }
+
+    #[test]
+    fn test_boolean_operation_dual_rule() {
+        // A nested square wound the same way is a hole under even-odd only
+        let stamp = rect_path(0.0, 0.0, 4.0, 4.0);
+        stamp.extend(&rect_path(1.0, 1.0, 3.0, 3.0));
+        let target = rect_path(1.5, 1.5, 2.5, 2.5);
+        let op = BooleanOperation::Intersection;
+
+        let even_odd =
+            boolean_operation_dual_rule(&target, &stamp, op, FillRule::NonZero, FillRule::EvenOdd)
+                .unwrap();
+        assert!(even_odd.paths.is_empty());
+
+        let non_zero =
+            boolean_operation_dual_rule(&target, &stamp, op, FillRule::NonZero, FillRule::NonZero)
+                .unwrap();
+        assert!((result_area(&non_zero) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_winding_image() {
+        // Two counterclockwise squares overlapping in the middle column
+        let path = rect_path(0.0, 0.0, 2.0, 1.0);
+        path.extend(&rect_path(1.0, 0.0, 3.0, 1.0));
+        let image = path.winding_image(4, 1, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        assert_eq!(image, vec![1, 2, 1, 0]);
+
+        // At half scale the grid covers the first square's corner, wound clockwise
+        let image = path.reversed().winding_image(2, 2, 0.5, 0.0, 0.0, 0.5, 0.0, 0.0);
+        assert_eq!(image, vec![-1; 4]);
+    }
+
+    #[test]
+    fn test_boolean_chain() {
+        let step = |path: BezierPath, operation| BooleanStep {
+            operand: Arc::new(path),
+            operation,
+        };
+        // ((A ∪ B) − C) ∩ D
+        let result = boolean_chain(
+            &rect_path(0.0, 0.0, 2.0, 2.0),
+            vec![
+                step(rect_path(2.0, 0.0, 4.0, 2.0), BooleanOperation::Union),
+                step(rect_path(1.0, 0.0, 3.0, 1.0), BooleanOperation::Difference),
+                step(rect_path(0.0, 0.0, 4.0, 1.5), BooleanOperation::Intersection),
+            ],
+            FillRule::NonZero,
+        )
+        .unwrap();
+        assert!((result_area(&result) - 4.0).abs() < 1e-9);
+
+        let start = rect_path(0.0, 0.0, 1.0, 1.0);
+        let unchanged = boolean_chain(&start, Vec::new(), FillRule::NonZero).unwrap();
+        assert!((result_area(&unchanged) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ensure_leading_moveto_line() {
+        let elements = [PathEl::LineTo(Point::new(1.0, 0.0)), PathEl::LineTo(Point::new(1.0, 1.0))];
+        let path = BezierPath::from_kurbo_path(elements.into_iter().collect());
+        assert_eq!(
+            path.ensure_leading_moveto().get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 0.0, y: 0.0 },
+                PathSegment::LineTo { x: 1.0, y: 0.0 },
+                PathSegment::LineTo { x: 1.0, y: 1.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ensure_leading_moveto_curve() {
+        let elements = [
+            PathEl::CurveTo(Point::new(0.0, 1.0), Point::new(1.0, 2.0), Point::new(2.0, 2.0)),
+            PathEl::ClosePath,
+            PathEl::MoveTo(Point::new(5.0, 5.0)),
+            PathEl::LineTo(Point::new(6.0, 5.0)),
+            PathEl::ClosePath,
+            // Starts where the closed subpath did
+            PathEl::LineTo(Point::new(5.0, 6.0)),
+        ];
+        let path = BezierPath::from_kurbo_path(elements.into_iter().collect());
+        let normalized = path.ensure_leading_moveto().get_segments();
+        assert_eq!(normalized.len(), 8);
+        assert_eq!(normalized[0], PathSegment::MoveTo { x: 0.0, y: 0.0 });
+        assert!(matches!(normalized[1], PathSegment::CurveTo { x: 2.0, y: 2.0, .. }));
+        assert_eq!(normalized[6], PathSegment::MoveTo { x: 5.0, y: 5.0 });
+        assert_eq!(normalized[7], PathSegment::LineTo { x: 5.0, y: 6.0 });
+
+        // Paths that already start every subpath with a MoveTo are unchanged
+        let square = rect_path(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(square.ensure_leading_moveto().get_segments(), square.get_segments());
+    }
+
+    #[test]
+    fn test_disjoint_boolean_skips_sweep() {
+        let a = rect_path(0.0, 0.0, 1.0, 1.0);
+        let b = rect_path(5.0, 5.0, 6.0, 7.0);
+        let rule = FillRule::NonZero;
+
+        let intersection = boolean_operation(&a, &b, BooleanOperation::Intersection, rule);
+        assert!(intersection.unwrap().paths.is_empty());
+        let difference = boolean_operation(&a, &b, BooleanOperation::Difference, rule).unwrap();
+        assert_eq!(difference.paths.len(), 1);
+        assert_eq!(difference.paths[0].get_segments(), a.get_segments());
+        let union = boolean_operation(&a, &b, BooleanOperation::Union, rule).unwrap();
+        assert!((result_area(&union) - 3.0).abs() < 1e-12);
+
+        // A self-crossing operand needs the sweep to resolve it
+        let bowtie = BezierPath::new();
+        bowtie.move_to(10.0, 0.0);
+        bowtie.line_to(12.0, 2.0);
+        bowtie.line_to(12.0, 0.0);
+        bowtie.line_to(10.0, 2.0);
+        bowtie.close_path();
+        let bowtie = bowtie.to_kurbo_path();
+        assert!(simple_contours(&bowtie, 1e-6).is_none());
+        let square = a.to_kurbo_path();
+        assert!(disjoint_boolean(&bowtie, &square, BooleanOperation::Difference).is_none());
+        assert!(disjoint_boolean(&square, &bowtie, BooleanOperation::Difference).is_some());
+
+        // Boxes closer than the sweep epsilon may be merged, so they are swept
+        let near = rect_path(1.0 + 1e-9, 0.0, 2.0, 1.0).to_kurbo_path();
+        assert!(disjoint_boolean(&square, &near, BooleanOperation::Union).is_none());
+    }
+
+    #[test]
+    fn test_center_on_origin() {
+        let l_shape = BezierPath::new();
+        l_shape.move_to(0.0, 0.0);
+        l_shape.line_to(3.0, 0.0);
+        l_shape.line_to(3.0, 1.0);
+        l_shape.line_to(1.0, 1.0);
+        l_shape.line_to(1.0, 3.0);
+        l_shape.line_to(0.0, 3.0);
+        l_shape.close_path();
+
+        // The centroid of the L is at (1.1, 1.1), and its box center at (1.5, 1.5)
+        let start = |path: Arc<BezierPath>| match path.get_segments()[0] {
+            PathSegment::MoveTo { x, y } => (x, y),
+            _ => panic!("expected MoveTo"),
+        };
+        let (x, y) = start(l_shape.center_on_origin(true));
+        assert!((x + 1.1).abs() < 1e-9 && (y + 1.1).abs() < 1e-9);
+        assert_eq!(start(l_shape.center_on_origin(false)), (-1.5, -1.5));
+
+        assert!(BezierPath::new().center_on_origin(true).is_empty());
+    }
+
+    #[test]
+    fn test_length() {
+        // The closing edge of the square counts, the open line has none
+        let path = rect_path(0.0, 0.0, 2.0, 1.0);
+        path.move_to(10.0, 0.0);
+        path.line_to(13.0, 4.0);
+        assert!((path.length(1e-9) - 11.0).abs() < 1e-9);
+
+        let circle =
+            BezierPath::from_kurbo_path(kurbo::Circle::new((0.0, 0.0), 1.0).to_path(1e-9));
+        assert!((circle.length(1e-9) - std::f64::consts::TAU).abs() < 1e-3);
+        assert_eq!(BezierPath::new().length(1e-9), 0.0);
+    }
+
+    #[test]
+    fn test_offset_segment() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(4.0, 0.0);
+        path.curve_to(6.0, 0.0, 6.0, 4.0, 4.0, 4.0);
+
+        // Left of a line heading along +x is +y
+        let line = path.offset_segment(0, 1.0, 1e-6).unwrap();
+        assert_eq!(
+            line.get_segments(),
+            vec![PathSegment::MoveTo { x: 0.0, y: 1.0 }, PathSegment::LineTo { x: 4.0, y: 1.0 }]
+        );
+        assert!(path.offset_segment(2, 1.0, 1e-6).is_none());
+
+        // A counterclockwise quarter circle of radius 10 offset inward by 1
+        let circle = kurbo::Circle::new((0.0, 0.0), 10.0).to_path(1e-9);
+        let arc = BezierPath::from_kurbo_path(circle);
+        let offset = arc.offset_segment(0, 1.0, 1e-4).unwrap().to_kurbo_path();
+        assert!(matches!(offset.elements()[0], PathEl::MoveTo(_)));
+        for seg in offset.segments() {
+            for t in [0.0, 0.3, 0.5, 1.0] {
+                assert!((seg.eval(t).distance(Point::ZERO) - 9.0).abs() < 0.01);
             }
-            _ => panic!("Expected LineTo segment"),
         }
+    }
 
-        match &segments[2] {
-            PathSegment::LineTo { x, y } => {
-                assert_eq!(*x, 1.0);
-                assert_eq!(*y, 1.0);
-            }
-            _ => panic!("Expected LineTo segment"),
+    #[test]
+    fn test_bytes_round_trip_is_bit_exact() {
+        let awkward = [0.1 + 0.2, -0.0, f64::MIN_POSITIVE / 3.0, 1e308, f64::NAN, -7.25];
+        let path = BezierPath::new();
+        path.move_to(awkward[0], awkward[1]);
+        path.quad_to(awkward[2], awkward[3], awkward[4], awkward[5]);
+        path.curve_to(awkward[5], awkward[4], awkward[3], awkward[2], awkward[1], awkward[0]);
+        path.close_path();
+        path.move_to(awkward[3], awkward[0]);
+
+        let bytes = path.to_bytes();
+        let decoded = BezierPath::from_bytes(bytes.clone()).unwrap();
+        let bits = |path: &BezierPath| -> Vec<u64> {
+            let flat = path.to_flat_commands();
+            let tags = flat.tags.iter().map(|&tag| u64::from(tag));
+            tags.chain(flat.coords.iter().map(|value| value.to_bits())).collect()
+        };
+        assert_eq!(bits(&decoded), bits(&path));
+        assert_eq!(decoded.to_bytes(), bytes);
+
+        // Every truncation fails cleanly, except at element boundaries
+        for len in 0..bytes.len() {
+            let boundary = [0, 17, 50, 99, 100].contains(&len);
+            assert_eq!(BezierPath::from_bytes(bytes[..len].to_vec()).is_ok(), boundary);
         }
+    }
 
-        match &segments[3] {
-            PathSegment::ClosePath => {}
-            _ => panic!("Expected ClosePath segment"),
+    #[test]
+    fn test_result_as_both() {
+        // A hole wound the same way as its outer contour, which nonzero would fill
+        let outer = rect_path(0.0, 0.0, 3.0, 3.0);
+        let hole = rect_path(1.0, 1.0, 2.0, 2.0);
+        let result = BooleanOperationResult {
+            paths: vec![Arc::new(outer), Arc::new(hole)],
+        };
+        let both = result_as_both(&result);
+        let probe = || vec![Point2D { x: 1.5, y: 1.5 }, Point2D { x: 0.5, y: 0.5 }];
+        assert_eq!(both.even_odd.contains_points(probe(), FillRule::EvenOdd), vec![false, true]);
+        assert_eq!(both.non_zero.contains_points(probe(), FillRule::NonZero), vec![false, true]);
+        assert!((both.non_zero.signed_area() - 8.0).abs() < 1e-9);
+
+        // The result's own contours are left as they were
+        assert!((result.paths[1].signed_area() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shape_constructors() {
+        let rect = BezierPath::rectangle(1.0, 2.0, 4.0, 3.0);
+        assert!((rect.area() - 12.0).abs() < 1e-12);
+        let expected = Rect { min_x: 1.0, min_y: 2.0, max_x: 5.0, max_y: 5.0 };
+        assert_eq!(rect.bounding_box(), Some(expected));
+        assert_eq!(rect.get_segments().last(), Some(&PathSegment::ClosePath));
+        assert!(BezierPath::rectangle(0.0, 0.0, -2.0, 1.0).area() < 1e-12);
+
+        let ellipse = BezierPath::ellipse(0.0, 0.0, 2.0, 1.0);
+        assert!((ellipse.area() - 2.0 * std::f64::consts::PI).abs() < 1e-2);
+        let bbox = ellipse.bounding_box().unwrap();
+        assert!((bbox.max_x - 2.0).abs() < 1e-9 && (bbox.max_y - 1.0).abs() < 1e-9);
+
+        // A radius larger than the rectangle allows gives a stadium
+        let stadium = BezierPath::rounded_rectangle(0.0, 0.0, 4.0, 2.0, 5.0);
+        let expected = 2.0 * 2.0 + std::f64::consts::PI;
+        assert!((stadium.area() - expected).abs() < 1e-2);
+        let square = BezierPath::rounded_rectangle(0.0, 0.0, 2.0, 2.0, -1.0);
+        assert!((square.area() - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shared_edges() {
+        // The squares share the middle of the vertical line x = 2
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(2.0, 1.0, 4.0, 3.0);
+        let shared = shared_edges(&a, &b, 1e-9);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(
+            shared[0].get_segments(),
+            vec![PathSegment::MoveTo { x: 2.0, y: 1.0 }, PathSegment::LineTo { x: 2.0, y: 2.0 }]
+        );
+
+        // Squares that only touch at a corner share nothing
+        let corner = rect_path(2.0, 2.0, 3.0, 3.0);
+        assert!(shared_edges(&a, &corner, 1e-9).is_empty());
+
+        // A curve shared in the opposite direction
+        let arc = BezierPath::new();
+        arc.move_to(0.0, 0.0);
+        arc.curve_to(0.0, 1.0, 1.0, 2.0, 2.0, 2.0);
+        arc.close_path();
+        let back = BezierPath::new();
+        back.move_to(2.0, 2.0);
+        back.curve_to(1.0, 2.0, 0.0, 1.0, 0.0, 0.0);
+        back.line_to(2.0, 0.0);
+        back.close_path();
+        let shared = shared_edges(&arc, &back, 1e-9);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].segment_count(), 2);
+    }
+
+    #[test]
+    fn test_stroke_outline() {
+        let line = BezierPath::new();
+        line.move_to(0.0, 0.0);
+        line.line_to(4.0, 0.0);
+        assert!(matches!(
+            line.stroke_outline(0.0, LineCapKind::Butt, LineJoinKind::Miter),
+            Err(LineSweeperError::InvalidArgument(_))
+        ));
+
+        let butt = line.stroke_outline(2.0, LineCapKind::Butt, LineJoinKind::Miter).unwrap();
+        assert!((butt.area() - 8.0).abs() < 1e-6);
+        let round = line.stroke_outline(2.0, LineCapKind::Round, LineJoinKind::Round).unwrap();
+        assert!((round.area() - (8.0 + std::f64::consts::PI)).abs() < 1e-2);
+        assert!(round.get_segments().contains(&PathSegment::ClosePath));
+    }
+
+    #[test]
+    fn test_rails() {
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.line_to(10.0, 10.0);
+
+        // Turning left, the left rail is cut back and the right one mitered
+        let rails = path.rails(1.0, 1e-6).unwrap();
+        let points = |path: &BezierPath| -> Vec<Point> {
+            path.to_kurbo_path().elements().iter().filter_map(|el| el.end_point()).collect()
+        };
+        let expected_left = [(0.0, 1.0), (9.0, 1.0), (9.0, 10.0)];
+        assert_eq!(points(&rails.left), expected_left.map(Point::from).to_vec());
+        let expected_right = [(0.0, -1.0), (10.0, -1.0), (11.0, -1.0), (11.0, 0.0), (11.0, 10.0)];
+        assert_eq!(points(&rails.right), expected_right.map(Point::from).to_vec());
+
+        // A closed square gives the square inset and outset by the half width
+        let square = rect_path(0.0, 0.0, 10.0, 10.0);
+        let rails = square.rails(1.0, 1e-6).unwrap();
+        assert!((rails.left.to_kurbo_path().area() - 64.0).abs() < 1e-9);
+        assert!((rails.right.to_kurbo_path().area() - 144.0).abs() < 1e-9);
+
+        assert!(matches!(path.rails(0.0, 1e-6), Err(LineSweeperError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_winding() {
+        // Two counterclockwise squares overlapping, and a clockwise one apart
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        path.extend(&rect_path(1.0, 1.0, 3.0, 3.0));
+        path.extend(&rect_path(5.0, 0.0, 6.0, 1.0).reversed());
+        assert_eq!(path.winding(0.5, 0.5), 1);
+        assert_eq!(path.winding(1.5, 1.5), 2);
+        assert_eq!(path.winding(5.5, 0.5), -1);
+        assert_eq!(path.winding(4.0, 4.0), 0);
+    }
+
+    #[test]
+    fn test_point_and_tangent_at() {
+        // A 4×2 rectangle, 12 long, starting at its bottom-left corner
+        let rect = rect_path(0.0, 0.0, 4.0, 2.0);
+        let close =
+            |a: Point2D, (x, y): (f64, f64)| (a.x - x).abs() < 1e-6 && (a.y - y).abs() < 1e-6;
+        assert!(close(rect.point_at(0.0).unwrap(), (0.0, 0.0)));
+        assert!(close(rect.point_at(0.25).unwrap(), (3.0, 0.0)));
+        assert!(close(rect.point_at(0.5).unwrap(), (4.0, 2.0)));
+        assert!(close(rect.point_at(2.0).unwrap(), (0.0, 0.0)));
+        assert!(close(rect.tangent_at(0.25).unwrap(), (1.0, 0.0)));
+        assert!(close(rect.tangent_at(0.4).unwrap(), (0.0, 1.0)));
+        assert!(close(rect.tangent_at(-1.0).unwrap(), (1.0, 0.0)));
+
+        // Halfway around a counterclockwise unit circle from (1, 0)
+        let circle = kurbo::Circle::new((0.0, 0.0), 1.0).to_path(1e-9);
+        let circle = BezierPath::from_kurbo_path(circle);
+        assert!(close(circle.point_at(0.5).unwrap(), (-1.0, 0.0)));
+        let tangent = circle.tangent_at(0.5).unwrap();
+        assert!(tangent.x.abs() < 1e-3 && (tangent.y + 1.0).abs() < 1e-3);
+
+        assert!(BezierPath::new().point_at(0.5).is_none());
+        assert!(BezierPath::new().tangent_at(0.5).is_none());
+    }
+
+    #[test]
+    fn test_concave_hull() {
+        // A U shape with a one-unit-wide notch from the top
+        let path = BezierPath::new();
+        path.move_to(0.0, 0.0);
+        for (x, y) in [(3.0, 0.0), (3.0, 3.0), (2.0, 3.0), (2.0, 1.0), (1.0, 1.0), (1.0, 3.0)] {
+            path.line_to(x, y);
         }
+        path.line_to(0.0, 3.0);
+        path.close_path();
+
+        let tight = path.concave_hull(0.5).to_kurbo_path();
+        assert!((tight.area() - 7.0).abs() < 1e-9);
+        assert!((path.concave_hull(10.0).to_kurbo_path().area() - 9.0).abs() < 1e-9);
+        assert!((path.concave_hull(f64::NAN).to_kurbo_path().area() - 9.0).abs() < 1e-9);
     }
 
     #[test]
-    fn test_curve_segment_extraction() {
+    fn test_nearest_point() {
+        let path = rect_path(0.0, 0.0, 2.0, 2.0);
+        path.move_to(5.0, 0.0);
+        path.line_to(5.0, 4.0);
+
+        // Closer to the open line than to the square
+        let nearest = path.nearest_point(4.0, 3.0, 1e-9).unwrap();
+        assert_eq!(nearest.point, Point2D { x: 5.0, y: 3.0 });
+        assert_eq!(nearest.distance, 1.0);
+
+        // From inside the square, the nearest edge is the closing one at x = 0
+        let nearest = path.nearest_point(0.5, 1.2, 1e-9).unwrap();
+        assert!(nearest.point.x.abs() < 1e-12 && (nearest.point.y - 1.2).abs() < 1e-12);
+        assert!((nearest.distance - 0.5).abs() < 1e-12);
+
+        let circle = kurbo::Circle::new((0.0, 0.0), 1.0).to_path(1e-9);
+        let nearest = BezierPath::from_kurbo_path(circle).nearest_point(3.0, 4.0, 1e-9).unwrap();
+        assert!((nearest.distance - 4.0).abs() < 1e-3);
+        assert!((nearest.point.x - 0.6).abs() < 1e-3 && (nearest.point.y - 0.8).abs() < 1e-3);
+
+        assert!(BezierPath::new().nearest_point(0.0, 0.0, 1e-9).is_none());
+    }
+
+    #[test]
+    fn test_checked_builders() {
+        let path = BezierPath::new();
+        path.try_move_to(0.0, 0.0).unwrap();
+        path.try_line_to(1.0, 0.0).unwrap();
+        path.try_quad_to(2.0, 0.0, 2.0, 1.0).unwrap();
+        path.try_curve_to(2.0, 2.0, 1.0, 2.0, 0.0, 2.0).unwrap();
+        assert_eq!(path.segment_count(), 4);
+
+        assert!(matches!(path.try_line_to(f64::NAN, 0.0), Err(LineSweeperError::NaNInput)));
+        assert!(matches!(
+            path.try_quad_to(f64::INFINITY, 0.0, 0.0, 0.0),
+            Err(LineSweeperError::InfiniteInput)
+        ));
+        assert!(matches!(
+            path.try_curve_to(0.0, 0.0, 0.0, 0.0, f64::NEG_INFINITY, f64::NAN),
+            Err(LineSweeperError::NaNInput)
+        ));
+        assert!(path.try_move_to(f64::INFINITY, 0.0).is_err());
+        assert_eq!(path.segment_count(), 4);
+    }
+
+    #[test]
+    fn test_intersections() {
+        // Overlapping squares cross at two points
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1.0, 1.0, 3.0, 3.0);
+        let mut points = intersections(&a, &b);
+        points.sort_by(|p, q| p.x.total_cmp(&q.x));
+        assert_eq!(points, vec![Point2D { x: 1.0, y: 2.0 }, Point2D { x: 2.0, y: 1.0 }]);
+
+        // Squares sharing part of an edge report the ends of the overlap
+        let c = rect_path(2.0, 1.0, 3.0, 4.0);
+        let mut points = intersections(&a, &c);
+        points.sort_by(|p, q| p.y.total_cmp(&q.y));
+        assert_eq!(points, vec![Point2D { x: 2.0, y: 1.0 }, Point2D { x: 2.0, y: 2.0 }]);
+
+        assert!(intersections(&a, &rect_path(5.0, 5.0, 6.0, 6.0)).is_empty());
+    }
+
+    #[test]
+    fn test_has_self_intersections() {
+        assert!(!rect_path(0.0, 0.0, 2.0, 2.0).has_self_intersections());
+
+        // A bowtie crosses itself between its distant edges
+        let bowtie = BezierPath::new();
+        bowtie.move_to(0.0, 0.0);
+        bowtie.line_to(2.0, 2.0);
+        bowtie.line_to(2.0, 0.0);
+        bowtie.line_to(0.0, 2.0);
+        bowtie.close_path();
+        assert!(bowtie.has_self_intersections());
+
+        // Two overlapping subpaths cross each other
+        let pair = rect_path(0.0, 0.0, 2.0, 2.0);
+        pair.extend(&rect_path(1.0, 1.0, 3.0, 3.0));
+        assert!(pair.has_self_intersections());
+        assert!(!BezierPath::new().has_self_intersections());
+    }
+
+    #[test]
+    fn test_offset() {
+        // Outset corners are round, so a 4×2 rectangle gains its perimeter and a unit circle
+        let rect = rect_path(0.0, 0.0, 4.0, 2.0);
+        let grown = rect.offset(1.0, 1e-4).unwrap();
+        let expected = 8.0 + 12.0 + std::f64::consts::PI;
+        assert!((grown.to_kurbo_path().area().abs() - expected).abs() < 1e-2);
+
+        let shrunk = rect.offset(-0.5, 1e-4).unwrap();
+        assert!((shrunk.to_kurbo_path().area().abs() - 3.0).abs() < 1e-6);
+        assert!(rect.offset(-2.0, 1e-4).unwrap().is_empty());
+        assert!(matches!(rect.offset(f64::NAN, 1e-4), Err(LineSweeperError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_centroid() {
+        let rect = rect_path(1.0, 1.0, 5.0, 3.0);
+        let centroid = rect.centroid().unwrap();
+        assert!((centroid.x - 3.0).abs() < 1e-9 && (centroid.y - 2.0).abs() < 1e-9);
+
+        // A 4×4 square with a 2×2 hole in its right half, both counterclockwise
+        let holed = rect_path(0.0, 0.0, 4.0, 4.0);
+        holed.extend(&rect_path(2.0, 1.0, 4.0, 3.0));
+        let centroid = holed.centroid().unwrap();
+        // (16·2 − 4·3) / 12
+        assert!((centroid.x - 20.0 / 12.0).abs() < 1e-9 && (centroid.y - 2.0).abs() < 1e-9);
+
+        assert!(BezierPath::new().centroid().is_none());
+        let line = BezierPath::new();
+        line.move_to(0.0, 0.0);
+        line.line_to(1.0, 1.0);
+        assert!(line.centroid().is_none());
+    }
+
+    #[test]
+    fn test_get_segments_as() {
         let path = BezierPath::new();
         path.move_to(0.0, 0.0);
-        path.curve_to(0.0, 1.0, 1.0, 1.0, 1.0, 0.0);
+        path.quad_to(3.0, 3.0, 6.0, 0.0);
         path.close_path();
+        path.quad_to(0.0, 3.0, -3.0, 3.0);
+        assert_eq!(path.get_segments_as(SegmentMode::Exact), path.get_segments());
 
-        let segments = path.get_segments();
-        assert_eq!(segments.len(), 3);
+        // Control points two thirds of the way to the quadratic's, from each end
+        let cubic = path.get_segments_as(SegmentMode::CubicOnly);
+        assert_eq!(
+            cubic[1],
+            PathSegment::CurveTo { cp1_x: 2.0, cp1_y: 2.0, cp2_x: 4.0, cp2_y: 2.0, x: 6.0, y: 0.0 }
+        );
+        // After a close, the next curve starts back at the subpath's start
+        let expected = PathSegment::CurveTo {
+            cp1_x: 0.0,
+            cp1_y: 2.0,
+            cp2_x: -1.0,
+            cp2_y: 3.0,
+            x: -3.0,
+            y: 3.0,
+        };
+        assert_eq!(cubic[3], expected);
 
-        match &segments[1] {
-            PathSegment::CurveTo { cp1_x, cp1_y, cp2_x, cp2_y, x, y } => {
-                assert_eq!(*cp1_x, 0.0);
-                assert_eq!(*cp1_y, 1.0);
-                assert_eq!(*cp2_x, 1.0);
-                assert_eq!(*cp2_y, 1.0);
-                assert_eq!(*x, 1.0);
-                assert_eq!(*y, 0.0);
-            }
-            _ => panic!("Expected CurveTo segment"),
+        let lines = path.get_segments_as(SegmentMode::LinesOnly { tolerance: 0.01 });
+        assert!(lines.len() > 4);
+        assert!(lines
+            .iter()
+            .all(|seg| !matches!(seg, PathSegment::QuadTo { .. } | PathSegment::CurveTo { .. })));
+    }
+
+    #[test]
+    fn test_translate_and_scale() {
+        let bounds = |path: &BezierPath| {
+            let rect = path.bounding_box().unwrap();
+            (rect.min_x, rect.min_y, rect.max_x, rect.max_y)
+        };
+        let path = rect_path(1.0, 1.0, 3.0, 2.0);
+        path.translate(1.0, -1.0);
+        assert_eq!(bounds(&path), (2.0, 0.0, 4.0, 1.0));
+        path.scale(2.0, 3.0);
+        assert_eq!(bounds(&path), (4.0, 0.0, 8.0, 3.0));
+
+        // Scaling about the center keeps it in place
+        path.scale_about(0.5, 2.0, 6.0, 1.5);
+        assert_eq!(bounds(&path), (5.0, -1.5, 7.0, 4.5));
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = rect_path(0.0, 0.0, 2.0, 2.0);
+        let b = rect_path(1e-7, 0.0, 2.0, 2.0 - 1e-7);
+        assert!(a.approx_eq(&b, 1e-6));
+        assert!(!a.approx_eq(&b, 1e-8));
+        assert!(a.approx_eq(&a, 0.0));
+
+        // Same shape traced the other way round
+        assert!(!a.approx_eq(&a.reversed(), 1.0));
+        let open = BezierPath::new();
+        open.set_segments(a.get_segments()[..4].to_vec()).unwrap();
+        assert!(!a.approx_eq(&open, 1.0));
+    }
+
+    #[test]
+    fn test_dash() {
+        let line = BezierPath::new();
+        line.move_to(0.0, 0.0);
+        line.line_to(5.0, 0.0);
+        for pattern in [vec![], vec![0.0, 0.0], vec![1.0, -1.0]] {
+            assert_eq!(line.dash(pattern, 0.0).get_segments(), line.get_segments());
         }
+
+        // Dashes at 0–1, 2–3 and 4–5, each its own subpath
+        let dashed = line.dash(vec![1.0, 1.0], 0.0);
+        let starts: Vec<PathSegment> = dashed
+            .get_segments()
+            .into_iter()
+            .filter(|seg| matches!(seg, PathSegment::MoveTo { .. }))
+            .collect();
+        assert_eq!(starts.len(), 3);
+        assert!((dashed.length(1e-9) - 3.0).abs() < 1e-9);
+
+        // Starting halfway into the first dash
+        let shifted = line.dash(vec![1.0, 1.0], 0.5);
+        assert!((shifted.length(1e-9) - 2.5).abs() < 1e-9);
     }
 
     #[test]
-    fn test_quad_segment_extraction() {
+    fn test_boolean_operation_with_empty_operand() {
+        let square = rect_path(0.0, 0.0, 2.0, 2.0);
+        let empty = BezierPath::new();
+        let paths = |a: &BezierPath, b: &BezierPath, operation| {
+            let result = boolean_operation(a, b, operation, FillRule::NonZero).unwrap();
+            result.paths.iter().map(|path| path.get_segments()).collect::<Vec<_>>()
+        };
+        let kept = vec![square.get_segments()];
+        let none: Vec<Vec<PathSegment>> = Vec::new();
+
+        assert_eq!(paths(&square, &empty, BooleanOperation::Union), kept);
+        assert_eq!(paths(&empty, &square, BooleanOperation::Union), kept);
+        assert_eq!(paths(&square, &empty, BooleanOperation::Intersection), none);
+        assert_eq!(paths(&empty, &square, BooleanOperation::Intersection), none);
+        assert_eq!(paths(&square, &empty, BooleanOperation::Difference), kept);
+        assert_eq!(paths(&empty, &square, BooleanOperation::Difference), none);
+        assert_eq!(paths(&square, &empty, BooleanOperation::Xor), kept);
+        assert_eq!(paths(&empty, &square, BooleanOperation::Xor), kept);
+        assert_eq!(paths(&empty, &empty, BooleanOperation::Union), none);
+
+        // Non-finite input is still an error
+        let bad = BezierPath::new();
+        bad.move_to(f64::NAN, 0.0);
+        bad.line_to(1.0, 1.0);
+        let result =
+            boolean_operation(&bad, &empty, BooleanOperation::Intersection, FillRule::NonZero);
+        assert!(matches!(result, Err(LineSweeperError::NaNInput)));
+    }
+
+    #[test]
+    fn test_elevate_quads_to_cubics() {
         let path = BezierPath::new();
         path.move_to(0.0, 0.0);
-        path.quad_to(0.5, 1.0, 1.0, 0.0);
+        path.line_to(0.0, 3.0);
+        path.quad_to(3.0, 6.0, 6.0, 3.0);
         path.close_path();
+        let original = path.to_kurbo_path();
+        path.elevate_quads_to_cubics();
 
         let segments = path.get_segments();
-        assert_eq!(segments.len(), 3);
-
-        match &segments[1] {
-            PathSegment::QuadTo { cp_x, cp_y, x, y } => {
-                assert_eq!(*cp_x, 0.5);
-                assert_eq!(*cp_y, 1.0);
-                assert_eq!(*x, 1.0);
-                assert_eq!(*y, 0.0);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[1], PathSegment::LineTo { x: 0.0, y: 3.0 });
+        assert_eq!(
+            segments[2],
+            PathSegment::CurveTo { cp1_x: 2.0, cp1_y: 5.0, cp2_x: 4.0, cp2_y: 5.0, x: 6.0, y: 3.0 }
+        );
+        for (before, after) in original.segments().zip(path.to_kurbo_path().segments()) {
+            for t in [0.25, 0.5, 0.75] {
+                assert!(before.eval(t).distance(after.eval(t)) < 1e-12);
             }
-            _ => panic!("Expected QuadTo segment"),
         }
     }
+
+    #[test]
+    fn test_polygon() {
+        let triangle = BezierPath::polygon(vec![0.0, 0.0, 2.0, 0.0, 1.0, 1.0], true).unwrap();
+        assert_eq!(
+            triangle.get_segments(),
+            vec![
+                PathSegment::MoveTo { x: 0.0, y: 0.0 },
+                PathSegment::LineTo { x: 2.0, y: 0.0 },
+                PathSegment::LineTo { x: 1.0, y: 1.0 },
+                PathSegment::ClosePath,
+            ]
+        );
+        let polyline = BezierPath::polygon(vec![0.0, 0.0, 2.0, 0.0], false).unwrap();
+        assert_eq!(polyline.segment_count(), 2);
+        assert!(BezierPath::polygon(Vec::new(), true).unwrap().is_empty());
+        assert!(matches!(
+            BezierPath::polygon(vec![0.0, 0.0, 1.0], false),
+            Err(LineSweeperError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_cleanup() {
+        // A square with a collinear vertex on each of two sides, one of them at
+        // the start, and a repeated point
+        let path = BezierPath::new();
+        path.move_to(1.0, 0.0);
+        path.line_to(2.0, 0.0);
+        path.line_to(2.0, 1.0);
+        path.line_to(2.0, 1.0);
+        path.line_to(2.0, 2.0);
+        path.line_to(0.0, 2.0);
+        path.line_to(0.0, 1.0 + 1e-9);
+        path.line_to(0.0, 0.0);
+        path.close_path();
+
+        let cleaned = path.cleanup(1e-6);
+        let points: Vec<Point> = cleaned
+            .to_kurbo_path()
+            .elements()
+            .iter()
+            .filter_map(|el| el.end_point())
+            .collect();
+        let expected = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)].map(Point::from);
+        assert_eq!(points, expected);
+        assert_eq!(cleaned.get_segments().last(), Some(&PathSegment::ClosePath));
+        assert!((cleaned.area() - path.area()).abs() < 1e-6);
+
+        // Curves are kept, and an open subpath with nothing left is dropped
+        let curve = BezierPath::new();
+        curve.move_to(0.0, 0.0);
+        curve.line_to(1.0, 0.0);
+        curve.curve_to(2.0, 0.0, 2.0, 1.0, 1.0, 1.0);
+        curve.move_to(5.0, 5.0);
+        curve.line_to(5.0, 5.0);
+        let cleaned = curve.cleanup(1e-6);
+        assert_eq!(cleaned.get_segments(), curve.get_segments()[..3].to_vec());
+    }
 }
 
 // Generate the UniFFI scaffolding